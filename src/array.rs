@@ -0,0 +1,67 @@
+//! Encodes arrays of any length for use with `#[serde(with = "cord::array")]`.
+//!
+//! serde only implements its traits for arrays of up to 32 elements. Arrays routed through
+//! this module are encoded exactly like the smaller ones: as a tuple with a length prefix.
+
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::ser::{SerializeTuple, Serializer};
+use serde::{Deserialize, Serialize};
+use std::convert::TryInto;
+use std::fmt::Formatter;
+use std::marker::PhantomData;
+
+pub fn serialize<S, T, const N: usize>(array: &[T; N], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Serialize,
+{
+    let mut tuple = serializer.serialize_tuple(N)?;
+    for element in array {
+        tuple.serialize_element(element)?;
+    }
+    tuple.end()
+}
+
+pub fn deserialize<'de, D, T, const N: usize>(deserializer: D) -> Result<[T; N], D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    deserializer.deserialize_tuple(
+        N,
+        ArrayVisitor {
+            marker: PhantomData,
+        },
+    )
+}
+
+struct ArrayVisitor<T, const N: usize> {
+    marker: PhantomData<fn() -> [T; N]>,
+}
+
+impl<'de, T, const N: usize> Visitor<'de> for ArrayVisitor<T, N>
+where
+    T: Deserialize<'de>,
+{
+    type Value = [T; N];
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        write!(formatter, "an array of length {N}")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        // Reserving all `N` elements up front would let a truncated input claim a buffer of
+        // `N * size_of::<T>()` bytes before any element has been read
+        let mut elements = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(N));
+        while let Some(element) = seq.next_element()? {
+            elements.push(element);
+        }
+        let len = elements.len();
+        elements
+            .try_into()
+            .map_err(|_| de::Error::invalid_length(len, &self))
+    }
+}
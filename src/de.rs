@@ -1,10 +1,23 @@
+use crate::embed::EMBED;
+use crate::fixint::FixedInt;
+use crate::options::{IntEncoding, Options, SeqEncoding, StrEncoding, StructEncoding};
+use crate::packed::packed_width;
 use crate::result::{CordError, CordResult};
-use crate::Set;
-use crate::{Bytes, DateTime};
+use crate::ser::encode_element;
+use crate::ser::FORMAT_VERSION;
+use crate::trace::trace;
+use crate::types::{RAW_BYTES, SET};
+use crate::wide;
+use crate::{
+    BorrowedBytes, Bytes, Date, DateTime, DateTimeWithOffset, DeltaSeq, Millis, NaiveDateTime,
+    Precision, Time, Timestamp,
+};
+use crate::{PresortedSet, RawBytes, Set, SortedSet};
 use integer_encoding::VarInt;
-use serde::de::IntoDeserializer;
+use serde::de::{DeserializeOwned, IntoDeserializer};
 use serde::{de, Deserialize, Deserializer, Serialize};
-use std::collections::HashSet;
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashSet};
 use std::fmt::Formatter;
 use std::hash::Hash;
 use std::marker::PhantomData;
@@ -13,19 +26,177 @@ pub fn deserialize<'a, T>(bytes: &'a [u8]) -> CordResult<T>
 where
     T: Deserialize<'a>,
 {
-    let mut deserializer = CordDeserializer::new(bytes);
+    deserialize_with_options(bytes, Options::default())
+}
+
+/// Checks that `bytes` holds a valid encoding of a `T` with nothing after it, discarding the
+/// decoded value.
+///
+/// This is not a walk with `serde::de::IgnoredAny`. The format is not self-describing, so the
+/// extent of a value cannot be told without its type, and the deserializer rejects
+/// `deserialize_ignored_any` for that reason. The only schema available is `T`'s own
+/// `Deserialize` implementation, so the check performs a full decode, at the cost of
+/// [`deserialize`]. Checking against a type that borrows its strings and byte arrays, such as
+/// `&str` in place of `String`, avoids most allocations.
+pub fn check<'a, T>(bytes: &'a [u8]) -> CordResult<()>
+where
+    T: Deserialize<'a>,
+{
+    deserialize::<T>(bytes).map(drop)
+}
+
+/// Decodes a value that owns all of its data, so that it may outlive `bytes`.
+pub fn deserialize_owned<T>(bytes: &[u8]) -> CordResult<T>
+where
+    T: DeserializeOwned,
+{
+    deserialize(bytes)
+}
+
+/// Serializes `value` and decodes the result, returning the decoded value.
+pub fn round_trip<T>(value: &T) -> CordResult<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    deserialize_owned(&crate::serialize(value)?)
+}
+
+/// Decodes a value written by [`serialize_versioned`](crate::serialize_versioned), rejecting
+/// any version byte other than [`FORMAT_VERSION`].
+pub fn deserialize_versioned<'a, T>(bytes: &'a [u8]) -> CordResult<T>
+where
+    T: Deserialize<'a>,
+{
+    match bytes.split_first() {
+        Some((&FORMAT_VERSION, value)) => deserialize(value),
+        Some(_) => Err(CordError::ValidationError("unsupported format version")),
+        None => Err(CordError::ValidationError("Unexpected end of stream")),
+    }
+}
+
+pub fn deserialize_with_options<'a, T>(bytes: &'a [u8], options: Options) -> CordResult<T>
+where
+    T: Deserialize<'a>,
+{
+    let mut deserializer = CordDeserializer::new(bytes, options);
     let result = T::deserialize(&mut deserializer)?;
     deserializer.end()?;
     Ok(result)
 }
 
+/// Decodes a value from the start of `bytes`, returning it along with the number of bytes
+/// it occupied. Unlike [`deserialize`], any bytes after the value are left unread.
+pub fn deserialize_counted<'a, T>(bytes: &'a [u8]) -> CordResult<(T, usize)>
+where
+    T: Deserialize<'a>,
+{
+    let mut deserializer = CordDeserializer::new(bytes, Options::default());
+    let result = T::deserialize(&mut deserializer)?;
+    Ok((result, deserializer.consumed(bytes)))
+}
+
+/// Decodes a value from the start of `bytes`, ignoring any bytes after it. Use
+/// [`deserialize_counted`] to locate the trailing bytes.
+pub fn deserialize_prefix<'a, T>(bytes: &'a [u8]) -> CordResult<T>
+where
+    T: Deserialize<'a>,
+{
+    deserialize_counted(bytes).map(|(result, _)| result)
+}
+
+/// Decodes a value from the start of `bytes`, returning it along with whether any bytes were
+/// left after it.
+pub fn deserialize_tolerant<'a, T>(bytes: &'a [u8]) -> CordResult<(T, bool)>
+where
+    T: Deserialize<'a>,
+{
+    deserialize_counted(bytes).map(|(result, consumed)| (result, consumed < bytes.len()))
+}
+
+/// Decodes successive values from a shared buffer, the counterpart to [`Encoder`](crate::Encoder).
+/// Unlike [`deserialize`], bytes left after a value are not an error.
+#[derive(Debug, Clone)]
+pub struct Decoder<'a> {
+    input: &'a [u8],
+    options: Options,
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(input: &'a [u8]) -> Self {
+        Self::with_options(input, Options::default())
+    }
+
+    pub fn with_options(input: &'a [u8], options: Options) -> Self {
+        Self { input, options }
+    }
+
+    /// Decodes the next value. On failure, the cursor is left where it was.
+    pub fn value<T>(&mut self) -> CordResult<T>
+    where
+        T: Deserialize<'a>,
+    {
+        let mut deserializer = CordDeserializer::new(self.input, self.options);
+        let result = T::deserialize(&mut deserializer)?;
+        self.input = deserializer.input;
+        Ok(result)
+    }
+
+    /// The bytes that have yet to be decoded.
+    pub fn remaining(&self) -> &'a [u8] {
+        self.input
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.input.is_empty()
+    }
+}
+
+// Adds lengths decoded from untrusted input
+pub(crate) fn checked_len_add(a: usize, b: usize) -> CordResult<usize> {
+    a.checked_add(b)
+        .ok_or(CordError::ValidationError("length overflow"))
+}
+
 struct CordDeserializer<'de> {
     input: &'de [u8],
+    options: Options,
+    dictionary: Vec<Cow<'de, str>>,
+    // Strings in `dictionary`, to reject a second literal of any of them
+    interned: HashSet<Cow<'de, str>>,
+    // Bytes that back-references may still repeat
+    expansion_budget: usize,
+    // Set while reading a struct embedded in its parent, which omits its field count. Cleared on
+    // entering any collection or struct, whose elements carry their own field counts.
+    embedded: bool,
+    // Set within `RawBytes`, whose tuple is read as that many bytes with no length prefix
+    raw_bytes: bool,
+    #[cfg(feature = "trace")]
+    input_len: usize,
 }
 
 impl<'de> CordDeserializer<'de> {
-    fn new(input: &'de [u8]) -> Self {
-        CordDeserializer { input }
+    fn new(input: &'de [u8], options: Options) -> Self {
+        CordDeserializer {
+            input,
+            options,
+            dictionary: Vec::new(),
+            interned: HashSet::new(),
+            expansion_budget: options.max_dictionary_expansion,
+            embedded: false,
+            raw_bytes: false,
+            #[cfg(feature = "trace")]
+            input_len: input.len(),
+        }
+    }
+
+    // Position within the input, for tracing
+    #[cfg(feature = "trace")]
+    fn offset(&self) -> usize {
+        self.input_len - self.input.len()
+    }
+
+    fn consumed(&self, bytes: &[u8]) -> usize {
+        bytes.len() - self.input.len()
     }
 
     fn end(&mut self) -> CordResult<()> {
@@ -62,36 +233,215 @@ impl<'de> CordDeserializer<'de> {
         match byte {
             0 => Ok(false),
             1 => Ok(true),
+            _ if self.options.lenient_bool => Ok(true),
             _ => Err(CordError::ValidationError("Invalid boolean variant")),
         }
     }
 
+    // Only the shortest encoding of a value is accepted, so that equal values have equal bytes
     fn parse_varint<T: VarInt>(&mut self) -> CordResult<T> {
-        T::decode_var(self.input)
-            .ok_or(CordError::ValidationError("Invalid varint"))
-            .and_then(|(value, size)| {
+        let (value, size) =
+            T::decode_var(self.input).ok_or(CordError::ValidationError("Invalid varint"))?;
+        let mut shortest = [0; 10];
+        let len = value.encode_var(&mut shortest);
+        if self.input.get(..size) != Some(&shortest[..len]) {
+            return Err(CordError::ValidationError("Non-canonical varint"));
+        }
+        self.consume(size)?;
+        Ok(value)
+    }
+
+    fn parse_int<T: VarInt + FixedInt>(&mut self) -> CordResult<T> {
+        match self.options.int_encoding {
+            IntEncoding::Varint => self.parse_varint::<T>(),
+            IntEncoding::FixedBigEndian => self.parse_fixed::<T>(),
+        }
+    }
+
+    fn parse_fixed<T: FixedInt>(&mut self) -> CordResult<T> {
+        let value = self
+            .input
+            .get(..T::SIZE)
+            .and_then(T::decode_fixed)
+            .ok_or(CordError::ValidationError("Unexpected end of stream"))?;
+        self.consume(T::SIZE)?;
+        Ok(value)
+    }
+
+    fn parse_wide_int<T: FixedInt>(
+        &mut self,
+        decode_var: fn(&[u8]) -> Option<(T, usize)>,
+    ) -> CordResult<T> {
+        match self.options.int_encoding {
+            IntEncoding::Varint => {
+                let (value, size) =
+                    decode_var(self.input).ok_or(CordError::ValidationError("Invalid varint"))?;
+                if size > 1 && self.input[size - 1] == 0 {
+                    return Err(CordError::ValidationError("Non-canonical varint"));
+                }
                 self.consume(size)?;
                 Ok(value)
-            })
+            }
+            IntEncoding::FixedBigEndian => self.parse_fixed::<T>(),
+        }
     }
 
     fn parse_variant_index(&mut self) -> CordResult<u32> {
-        self.parse_varint::<u32>()
+        if self.options.fixed_variant_index {
+            let bytes = self.parse_slice(4)?;
+            return Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]));
+        }
+        self.parse_int::<u32>()
+    }
+
+    fn parse_slice(&mut self, len: usize) -> CordResult<&'de [u8]> {
+        if len > self.input.len() {
+            return Err(CordError::ValidationError("Unexpected end of stream"));
+        }
+        let (slice, rest) = self.input.split_at(len);
+        self.input = rest;
+        Ok(slice)
     }
 
-    fn parse_bytes(&mut self) -> CordResult<&'de [u8]> {
+    fn parse_bytes(&mut self) -> CordResult<Cow<'de, [u8]>> {
+        match self.options.str_encoding {
+            StrEncoding::LengthPrefixed => self.parse_prefixed_bytes().map(Cow::Borrowed),
+            StrEncoding::Escaped => self.parse_escaped_bytes(),
+            StrEncoding::NulTerminated => self.parse_nul_terminated_bytes().map(Cow::Borrowed),
+        }
+    }
+
+    fn parse_prefixed_bytes(&mut self) -> CordResult<&'de [u8]> {
         let len = self.parse_varint::<usize>()?;
-        let slice = self
+        if len > self.input.len() {
+            return Err(CordError::ValidationError("Length prefix exceeds input"));
+        }
+        let (slice, rest) = self.input.split_at(len);
+        self.input = rest;
+        Ok(slice)
+    }
+
+    fn parse_nul_terminated_bytes(&mut self) -> CordResult<&'de [u8]> {
+        let len = self
             .input
-            .get(..len)
+            .iter()
+            .position(|byte| *byte == 0)
             .ok_or(CordError::ValidationError("Unexpected end of bytestream"))?;
-        self.input = &self.input[len..];
+        let slice = &self.input[..len];
+        self.input = &self.input[len + 1..];
         Ok(slice)
     }
 
-    fn parse_string(&mut self) -> CordResult<&'de str> {
-        let slice = self.parse_bytes()?;
-        std::str::from_utf8(slice).map_err(|_| CordError::ValidationError("Invalid UTF-8 string"))
+    fn parse_escaped_bytes(&mut self) -> CordResult<Cow<'de, [u8]>> {
+        let input = self.input;
+        let mut unescaped: Option<Vec<u8>> = None;
+        let mut position = 0;
+
+        loop {
+            let zero = input[position..]
+                .iter()
+                .position(|byte| *byte == 0)
+                .map(|offset| position + offset)
+                .ok_or(CordError::ValidationError("Unexpected end of bytestream"))?;
+
+            match input.get(zero + 1) {
+                Some(1) => {
+                    self.input = &input[zero + 2..];
+                    return Ok(match unescaped {
+                        Some(mut buffer) => {
+                            buffer.extend_from_slice(&input[position..zero]);
+                            Cow::Owned(buffer)
+                        }
+                        None => Cow::Borrowed(&input[..zero]),
+                    });
+                }
+                Some(0xff) => {
+                    let buffer = unescaped.get_or_insert_with(Vec::new);
+                    buffer.extend_from_slice(&input[position..zero]);
+                    buffer.push(0);
+                    position = zero + 2;
+                }
+                _ => return Err(CordError::ValidationError("Invalid escape sequence")),
+            }
+        }
+    }
+
+    fn parse_field_count(&mut self, fields: usize) -> CordResult<usize> {
+        let (len, strict) = match self.options.struct_encoding {
+            StructEncoding::Positional => return Ok(fields),
+            StructEncoding::LengthPrefixed => (self.parse_varint::<usize>()?, false),
+            StructEncoding::StrictLengthPrefixed => (self.parse_varint::<usize>()?, true),
+        };
+        if len > fields || (strict && len < fields) {
+            return Err(CordError::ValidationError("Unexpected field count"));
+        }
+        Ok(len)
+    }
+
+    fn check_collection_len(&self, len: usize) -> CordResult<()> {
+        match self.options.max_collection_len {
+            Some(max) if len > max => Err(CordError::ValidationError(
+                "Collection length exceeds limit",
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    // Reads the element count of the next chunk and adds it to the running `total`
+    fn parse_chunk_len(&mut self, total: &mut usize) -> CordResult<usize> {
+        let len = self.parse_varint::<usize>()?;
+        *total = checked_len_add(*total, len)?;
+        self.check_collection_len(*total)?;
+        Ok(len)
+    }
+
+    fn parse_dictionary_string(&mut self) -> CordResult<Cow<'de, str>> {
+        match self.parse_varint::<usize>()? {
+            0 => {
+                let string = self.parse_string()?;
+                // The encoder refers back to any string it has already written
+                if !self.interned.insert(string.clone()) {
+                    return Err(CordError::ValidationError("Repeated dictionary literal"));
+                }
+                self.dictionary.push(string.clone());
+                Ok(string)
+            }
+            reference => {
+                let string = self
+                    .dictionary
+                    .get(reference - 1)
+                    .ok_or(CordError::ValidationError("Invalid dictionary reference"))?;
+                self.expansion_budget = self.expansion_budget.checked_sub(string.len()).ok_or(
+                    CordError::ValidationError("Dictionary expansion exceeds limit"),
+                )?;
+                Ok(string.clone())
+            }
+        }
+    }
+
+    // Mirrors the serializer, which writes strings within map entries in full
+    fn without_dictionary<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> CordResult<T>,
+    ) -> CordResult<T> {
+        let enabled = std::mem::replace(&mut self.options.string_dictionary, false);
+        let result = f(self);
+        self.options.string_dictionary = enabled;
+        result
+    }
+
+    fn parse_string(&mut self) -> CordResult<Cow<'de, str>> {
+        let bytes = self.parse_bytes()?;
+        if let Some(max) = self.options.max_str_len {
+            if bytes.len() > max {
+                return Err(CordError::ValidationError("string too long"));
+            }
+        }
+        match bytes {
+            Cow::Borrowed(slice) => std::str::from_utf8(slice).map(Cow::Borrowed).ok(),
+            Cow::Owned(buffer) => String::from_utf8(buffer).map(Cow::Owned).ok(),
+        }
+        .ok_or(CordError::ValidationError("Invalid UTF-8 string"))
     }
 }
 
@@ -102,7 +452,7 @@ macro_rules! deserialize_varints {
             where
                 V: de::Visitor<'de>,
             {
-                visitor.$visit(self.parse_varint::<$int>()?)
+                visitor.$visit(self.parse_int::<$int>()?)
             }
         )*
     };
@@ -128,7 +478,7 @@ impl<'de> de::Deserializer<'de> for &mut CordDeserializer<'de> {
     where
         V: de::Visitor<'de>,
     {
-        visitor.visit_bytes(self.parse_bytes()?)
+        visitor.visit_bytes(&self.parse_bytes()?)
     }
 
     fn deserialize_bool<V>(self, visitor: V) -> CordResult<V::Value>
@@ -149,6 +499,20 @@ impl<'de> de::Deserializer<'de> for &mut CordDeserializer<'de> {
         (u64, deserialize_u64, visit_u64)
     );
 
+    fn deserialize_i128<V>(self, visitor: V) -> CordResult<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_i128(self.parse_wide_int(wide::decode_i128)?)
+    }
+
+    fn deserialize_u128<V>(self, visitor: V) -> CordResult<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_u128(self.parse_wide_int(wide::decode_u128)?)
+    }
+
     deserialize_unsupported!(
         (f32, deserialize_f32, visit_f32),
         (f64, deserialize_f64, visit_f64),
@@ -159,7 +523,15 @@ impl<'de> de::Deserializer<'de> for &mut CordDeserializer<'de> {
     where
         V: de::Visitor<'de>,
     {
-        visitor.visit_borrowed_str(self.parse_string()?)
+        let string = if self.options.string_dictionary {
+            self.parse_dictionary_string()?
+        } else {
+            self.parse_string()?
+        };
+        match string {
+            Cow::Borrowed(string) => visitor.visit_borrowed_str(string),
+            Cow::Owned(string) => visitor.visit_string(string),
+        }
     }
 
     fn deserialize_string<V>(self, visitor: V) -> CordResult<V::Value>
@@ -173,7 +545,10 @@ impl<'de> de::Deserializer<'de> for &mut CordDeserializer<'de> {
     where
         V: de::Visitor<'de>,
     {
-        visitor.visit_borrowed_bytes(self.parse_bytes()?)
+        match self.parse_bytes()? {
+            Cow::Borrowed(bytes) => visitor.visit_borrowed_bytes(bytes),
+            Cow::Owned(bytes) => visitor.visit_byte_buf(bytes),
+        }
     }
 
     fn deserialize_byte_buf<V>(self, visitor: V) -> CordResult<V::Value>
@@ -192,6 +567,7 @@ impl<'de> de::Deserializer<'de> for &mut CordDeserializer<'de> {
         match byte {
             0 => visitor.visit_none(),
             1 => visitor.visit_some(self),
+            _ if self.options.lenient_bool => visitor.visit_some(self),
             _ => Err(CordError::ValidationError("Invalid option variant")),
         }
     }
@@ -210,25 +586,64 @@ impl<'de> de::Deserializer<'de> for &mut CordDeserializer<'de> {
         self.deserialize_unit(visitor)
     }
 
-    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> CordResult<V::Value>
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> CordResult<V::Value>
     where
         V: de::Visitor<'de>,
     {
-        visitor.visit_newtype_struct(&mut *self)
+        if let Some(width) = packed_width(name) {
+            if self.options.int_encoding == IntEncoding::FixedBigEndian
+                && self.options.seq_encoding == SeqEncoding::LengthPrefixed
+            {
+                let len = self.parse_varint::<usize>()?;
+                self.check_collection_len(len)?;
+                let size = len
+                    .checked_mul(width)
+                    .ok_or(CordError::ValidationError("Unexpected end of stream"))?;
+                return visitor.visit_borrowed_bytes(self.parse_slice(size)?);
+            }
+        }
+        // Elements lie in the input as `encode_element` writes them only under the default
+        // options, so only then is their order checked against the input. This relies on
+        // decoding accepting only canonical varints, so that equal elements have equal bytes.
+        if name == SET && self.options.encodes_like_default() {
+            let len = self.parse_varint::<usize>()?;
+            self.check_collection_len(len)?;
+            return visitor.visit_seq(SetDeserializer::new(self, len));
+        }
+        self.embedded = name == EMBED;
+        self.raw_bytes = name == RAW_BYTES;
+        let value = visitor.visit_newtype_struct(&mut *self);
+        self.embedded = false;
+        self.raw_bytes = false;
+        value
     }
 
     fn deserialize_seq<V>(self, visitor: V) -> CordResult<V::Value>
     where
         V: de::Visitor<'de>,
     {
-        let len = self.parse_varint::<usize>()?;
-        visitor.visit_seq(SeqDeserializer::new(self, len))
+        match self.options.seq_encoding {
+            SeqEncoding::LengthPrefixed => {
+                let len = self.parse_varint::<usize>()?;
+                self.check_collection_len(len)?;
+                visitor.visit_seq(SeqDeserializer::new(self, len))
+            }
+            SeqEncoding::Terminated => visitor.visit_seq(TerminatedSeqDeserializer::new(self)),
+            SeqEncoding::Chunked => visitor.visit_seq(ChunkedSeqDeserializer::new(self)),
+        }
     }
 
     fn deserialize_tuple<V>(self, len: usize, visitor: V) -> CordResult<V::Value>
     where
         V: de::Visitor<'de>,
     {
+        if std::mem::take(&mut self.raw_bytes) {
+            let bytes = self.parse_slice(len)?;
+            return visitor.visit_borrowed_bytes(bytes);
+        }
+        if self.parse_varint::<usize>()? != len {
+            return Err(CordError::ValidationError("Unexpected tuple length"));
+        }
         visitor.visit_seq(SeqDeserializer::new(self, len))
     }
 
@@ -244,11 +659,20 @@ impl<'de> de::Deserializer<'de> for &mut CordDeserializer<'de> {
         visitor.visit_seq(SeqDeserializer::new(self, len))
     }
 
-    fn deserialize_map<V>(self, _visitor: V) -> CordResult<V::Value>
+    fn deserialize_map<V>(self, visitor: V) -> CordResult<V::Value>
     where
         V: de::Visitor<'de>,
     {
-        Err(CordError::NotSupported("map"))
+        let remaining = match self.options.seq_encoding {
+            SeqEncoding::LengthPrefixed => {
+                let len = self.parse_varint::<usize>()?;
+                self.check_collection_len(len)?;
+                Some(len)
+            }
+            SeqEncoding::Terminated => None,
+            SeqEncoding::Chunked => Some(0),
+        };
+        visitor.visit_map(MapDeserializer::new(self, remaining))
     }
 
     fn deserialize_struct<V>(
@@ -260,7 +684,12 @@ impl<'de> de::Deserializer<'de> for &mut CordDeserializer<'de> {
     where
         V: de::Visitor<'de>,
     {
-        visitor.visit_seq(SeqDeserializer::new(self, fields.len()))
+        let len = if std::mem::take(&mut self.embedded) {
+            fields.len()
+        } else {
+            self.parse_field_count(fields.len())?
+        };
+        visitor.visit_seq(StructDeserializer::new(self, fields, len))
     }
 
     fn deserialize_enum<V>(
@@ -297,6 +726,7 @@ struct SeqDeserializer<'a, 'de: 'a> {
 
 impl<'a, 'de> SeqDeserializer<'a, 'de> {
     fn new(de: &'a mut CordDeserializer<'de>, remaining: usize) -> Self {
+        de.embedded = false;
         Self { de, remaining }
     }
 }
@@ -312,12 +742,282 @@ impl<'de> de::SeqAccess<'de> for SeqDeserializer<'_, 'de> {
             Ok(None)
         } else {
             self.remaining -= 1;
+            trace!("deserializing element at offset {}", self.de.offset());
             seed.deserialize(&mut *self.de).map(Some)
         }
     }
 
     fn size_hint(&self) -> Option<usize> {
-        Some(self.remaining)
+        // Elements occupy at least one byte unless zero-sized, so never hint at more than remain
+        Some(self.remaining.min(self.de.input.len()))
+    }
+}
+
+// Rejects elements whose encoding does not sort strictly after that of the previous one,
+// comparing the input they were decoded from as `MapDeserializer` does for keys
+struct SetDeserializer<'a, 'de: 'a> {
+    de: &'a mut CordDeserializer<'de>,
+    remaining: usize,
+    previous_element: Option<&'de [u8]>,
+}
+
+impl<'a, 'de> SetDeserializer<'a, 'de> {
+    fn new(de: &'a mut CordDeserializer<'de>, remaining: usize) -> Self {
+        de.embedded = false;
+        Self {
+            de,
+            remaining,
+            previous_element: None,
+        }
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for SetDeserializer<'_, 'de> {
+    type Error = CordError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> CordResult<Option<T::Value>>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        trace!("deserializing element at offset {}", self.de.offset());
+
+        let start = self.de.input;
+        let element = seed.deserialize(&mut *self.de)?;
+        let encoded = &start[..start.len() - self.de.input.len()];
+        if self
+            .previous_element
+            .map_or(false, |previous| previous >= encoded)
+        {
+            return Err(de::Error::custom("unordered set"));
+        }
+        self.previous_element = Some(encoded);
+        Ok(Some(element))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining.min(self.de.input.len()))
+    }
+}
+
+// Attributes errors to the field being decoded, mirroring `SerializeStruct::serialize_field`
+struct StructDeserializer<'a, 'de: 'a> {
+    de: &'a mut CordDeserializer<'de>,
+    fields: &'static [&'static str],
+    len: usize,
+    index: usize,
+}
+
+impl<'a, 'de> StructDeserializer<'a, 'de> {
+    fn new(de: &'a mut CordDeserializer<'de>, fields: &'static [&'static str], len: usize) -> Self {
+        de.embedded = false;
+        Self {
+            de,
+            fields,
+            len,
+            index: 0,
+        }
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for StructDeserializer<'_, 'de> {
+    type Error = CordError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> CordResult<Option<T::Value>>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        if self.index == self.len {
+            return Ok(None);
+        }
+        let field = self.fields.get(self.index).copied();
+        self.index += 1;
+
+        trace!(
+            "deserializing field {} at offset {}",
+            field.unwrap_or("?"),
+            self.de.offset()
+        );
+        seed.deserialize(&mut *self.de)
+            .map(Some)
+            .map_err(|err| match field {
+                Some(field) => err.in_field(field),
+                None => err,
+            })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some((self.len - self.index).min(self.de.input.len()))
+    }
+}
+
+struct TerminatedSeqDeserializer<'a, 'de: 'a> {
+    de: &'a mut CordDeserializer<'de>,
+    len: usize,
+}
+
+impl<'a, 'de> TerminatedSeqDeserializer<'a, 'de> {
+    fn new(de: &'a mut CordDeserializer<'de>) -> Self {
+        de.embedded = false;
+        Self { de, len: 0 }
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for TerminatedSeqDeserializer<'_, 'de> {
+    type Error = CordError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> CordResult<Option<T::Value>>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        match self.de.next()? {
+            0 => Ok(None),
+            1 => {
+                self.len = checked_len_add(self.len, 1)?;
+                self.de.check_collection_len(self.len)?;
+                trace!("deserializing element at offset {}", self.de.offset());
+                seed.deserialize(&mut *self.de).map(Some)
+            }
+            _ => Err(CordError::ValidationError("Invalid sequence marker")),
+        }
+    }
+}
+
+struct ChunkedSeqDeserializer<'a, 'de: 'a> {
+    de: &'a mut CordDeserializer<'de>,
+    remaining: usize,
+    len: usize,
+}
+
+impl<'a, 'de> ChunkedSeqDeserializer<'a, 'de> {
+    fn new(de: &'a mut CordDeserializer<'de>) -> Self {
+        de.embedded = false;
+        Self {
+            de,
+            remaining: 0,
+            len: 0,
+        }
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for ChunkedSeqDeserializer<'_, 'de> {
+    type Error = CordError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> CordResult<Option<T::Value>>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        if self.remaining == 0 {
+            self.remaining = self.de.parse_chunk_len(&mut self.len)?;
+            if self.remaining == 0 {
+                return Ok(None);
+            }
+        }
+        self.remaining -= 1;
+        trace!("deserializing element at offset {}", self.de.offset());
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining.min(self.de.input.len()))
+    }
+}
+
+// Tracks the encoded bytes of the previous key in order to reject maps whose keys
+// are not strictly ascending, which keeps their encoding canonical. Under
+// `Options::trust_key_ord` keys may come in any order, so every key seen is kept instead to
+// still reject duplicates.
+struct MapDeserializer<'a, 'de: 'a> {
+    de: &'a mut CordDeserializer<'de>,
+    remaining: Option<usize>,
+    len: usize,
+    previous_key: Option<&'de [u8]>,
+    keys: HashSet<&'de [u8]>,
+}
+
+impl<'a, 'de> MapDeserializer<'a, 'de> {
+    fn new(de: &'a mut CordDeserializer<'de>, remaining: Option<usize>) -> Self {
+        de.embedded = false;
+        Self {
+            de,
+            remaining,
+            len: 0,
+            previous_key: None,
+            keys: HashSet::new(),
+        }
+    }
+
+    fn has_next(&mut self) -> CordResult<bool> {
+        match self.remaining {
+            Some(0) if self.de.options.seq_encoding == SeqEncoding::Chunked => {
+                match self.de.parse_chunk_len(&mut self.len)? {
+                    0 => Ok(false),
+                    len => {
+                        self.remaining = Some(len - 1);
+                        Ok(true)
+                    }
+                }
+            }
+            Some(0) => Ok(false),
+            Some(remaining) => {
+                self.remaining = Some(remaining - 1);
+                Ok(true)
+            }
+            None => match self.de.next()? {
+                0 => Ok(false),
+                1 => {
+                    self.len = checked_len_add(self.len, 1)?;
+                    self.de.check_collection_len(self.len)?;
+                    Ok(true)
+                }
+                _ => Err(CordError::ValidationError("Invalid sequence marker")),
+            },
+        }
+    }
+}
+
+impl<'de> de::MapAccess<'de> for MapDeserializer<'_, 'de> {
+    type Error = CordError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> CordResult<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        if !self.has_next()? {
+            return Ok(None);
+        }
+
+        let start = self.de.input;
+        let key = self.de.without_dictionary(|de| seed.deserialize(de))?;
+        let encoded = &start[..start.len() - self.de.input.len()];
+
+        if self.de.options.trust_key_ord {
+            if !self.keys.insert(encoded) {
+                return Err(CordError::ValidationError("Duplicate map key"));
+            }
+        } else if self
+            .previous_key
+            .map_or(false, |previous| previous >= encoded)
+        {
+            return Err(CordError::ValidationError("Unordered map keys"));
+        }
+        self.previous_key = Some(encoded);
+        Ok(Some(key))
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> CordResult<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        self.de.without_dictionary(|de| seed.deserialize(de))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.remaining
+            .map(|remaining| remaining.min(self.de.input.len()))
     }
 }
 
@@ -329,6 +1029,7 @@ impl<'de> de::EnumAccess<'de> for &mut CordDeserializer<'de> {
     where
         V: de::DeserializeSeed<'de>,
     {
+        trace!("deserializing variant at offset {}", self.offset());
         let variant_index = self.parse_variant_index()?;
         let result: CordResult<V::Value> = seed.deserialize(variant_index.into_deserializer());
         Ok((result?, self))
@@ -353,14 +1054,15 @@ impl<'de> de::VariantAccess<'de> for &mut CordDeserializer<'de> {
     where
         V: de::Visitor<'de>,
     {
-        de::Deserializer::deserialize_tuple(self, len, visitor)
+        visitor.visit_seq(SeqDeserializer::new(self, len))
     }
 
     fn struct_variant<V>(self, fields: &'static [&'static str], visitor: V) -> CordResult<V::Value>
     where
         V: de::Visitor<'de>,
     {
-        de::Deserializer::deserialize_tuple(self, fields.len(), visitor)
+        let len = self.parse_field_count(fields.len())?;
+        visitor.visit_seq(StructDeserializer::new(self, fields, len))
     }
 }
 
@@ -397,44 +1099,211 @@ impl<'de> de::Deserialize<'de> for Bytes {
     }
 }
 
-struct SetVisitor<T: Hash + PartialEq> {
-    marker: PhantomData<fn() -> Set<T>>,
-}
+struct RawBytesVisitor<const N: usize>;
 
-impl<T: Hash + PartialEq> SetVisitor<T> {
-    fn new() -> Self {
-        SetVisitor {
-            marker: PhantomData,
+impl<'de, const N: usize> de::Visitor<'de> for RawBytesVisitor<N> {
+    type Value = RawBytes<N>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        write!(formatter, "{N} bytes")
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> CordResult<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(N, self)
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> CordResult<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        v.try_into()
+            .map(RawBytes)
+            .map_err(|_| de::Error::invalid_length(v.len(), &self))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> CordResult<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut bytes = [0_u8; N];
+        for (index, byte) in bytes.iter_mut().enumerate() {
+            *byte = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(index, &self))?;
+        }
+        if seq.next_element::<u8>()?.is_some() {
+            return Err(de::Error::invalid_length(N + 1, &self));
         }
+        Ok(RawBytes(bytes))
+    }
+}
+
+impl<'de, const N: usize> de::Deserialize<'de> for RawBytes<N> {
+    fn deserialize<D>(deserializer: D) -> CordResult<RawBytes<N>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct(RAW_BYTES, RawBytesVisitor)
+    }
+}
+
+struct BorrowedBytesVisitor;
+
+impl<'de> de::Visitor<'de> for BorrowedBytesVisitor {
+    type Value = BorrowedBytes<'de>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("bytes")
+    }
+
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> CordResult<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(BorrowedBytes(Cow::Borrowed(v)))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> CordResult<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(BorrowedBytes(Cow::Owned(v.to_vec())))
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> CordResult<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(BorrowedBytes(Cow::Owned(v)))
+    }
+}
+
+impl<'de: 'a, 'a> de::Deserialize<'de> for BorrowedBytes<'a> {
+    fn deserialize<D>(deserializer: D) -> CordResult<BorrowedBytes<'a>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_bytes(BorrowedBytesVisitor)
+    }
+}
+
+// Upper bound on the capacity reserved up front from an untrusted length prefix
+pub(crate) const MAX_PREALLOCATED_ELEMENTS: usize = 4096;
+
+// Collections into which the elements of a set are decoded
+pub(crate) trait SetElements<T>: Default {
+    fn reserve(&mut self, additional: usize);
+
+    // Returns whether the element was not present already
+    fn insert_element(&mut self, element: T) -> bool;
+}
+
+impl<T: Hash + Eq> SetElements<T> for HashSet<T> {
+    fn reserve(&mut self, additional: usize) {
+        HashSet::reserve(self, additional);
     }
+
+    fn insert_element(&mut self, element: T) -> bool {
+        self.insert(element)
+    }
+}
+
+impl<T: Ord> SetElements<T> for BTreeSet<T> {
+    fn reserve(&mut self, _additional: usize) {}
+
+    fn insert_element(&mut self, element: T) -> bool {
+        self.insert(element)
+    }
+}
+
+impl<T> SetElements<T> for Vec<T> {
+    fn reserve(&mut self, additional: usize) {
+        Vec::reserve(self, additional);
+    }
+
+    fn insert_element(&mut self, element: T) -> bool {
+        self.push(element);
+        true
+    }
+}
+
+// Decodes a set written under the reserved name `SET`, rejecting elements that are out of
+// canonical order or repeat the previous element
+pub(crate) fn deserialize_set<'de, D, T, C>(deserializer: D) -> CordResult<C, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Serialize + Deserialize<'de>,
+    C: SetElements<T>,
+{
+    deserializer.deserialize_newtype_struct(
+        SET,
+        SetVisitor {
+            encode: false,
+            marker: PhantomData,
+        },
+    )
+}
+
+struct SetVisitor<T, C> {
+    // Set when the deserializer leaves it to the visitor to check the order of the elements,
+    // which it does by encoding each of them
+    encode: bool,
+    marker: PhantomData<fn() -> (T, C)>,
 }
 
-impl<'de, T> de::Visitor<'de> for SetVisitor<T>
+impl<'de, T, C> de::Visitor<'de> for SetVisitor<T, C>
 where
-    T: Hash + Eq + Serialize + Deserialize<'de>,
+    T: Serialize + Deserialize<'de>,
+    C: SetElements<T>,
 {
-    type Value = Set<T>;
+    type Value = C;
 
     fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
         formatter.write_str("set")
     }
 
+    // Cord checks the order against the input itself and visits the elements directly, except
+    // under options that encode them differently than the canonical order assumes
+    fn visit_newtype_struct<D>(self, deserializer: D) -> CordResult<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(SetVisitor {
+            encode: true,
+            marker: PhantomData,
+        })
+    }
+
     fn visit_seq<A>(self, mut seq: A) -> CordResult<Self::Value, A::Error>
     where
         A: de::SeqAccess<'de>,
     {
-        let mut hashset: HashSet<T> = HashSet::with_capacity(seq.size_hint().unwrap_or(0));
-        let mut previous_element: Option<Vec<u8>> = None;
+        let mut elements = C::default();
+        elements.reserve(seq.size_hint().unwrap_or(0).min(MAX_PREALLOCATED_ELEMENTS));
+
+        // The two encodings trade places after every element, so that their buffers are reused
+        let mut previous_element = Vec::new();
+        let mut current_element = Vec::new();
+        let mut first = true;
         while let Some(element) = seq.next_element::<T>()? {
-            let current_element = Some(crate::serialize(&element).unwrap());
-            if previous_element.is_some() && previous_element > current_element {
-                return Err(de::Error::custom("unordered set"));
+            if self.encode {
+                current_element.clear();
+                encode_element(&mut current_element, &element).map_err(de::Error::custom)?;
+                if !first && previous_element >= current_element {
+                    return Err(de::Error::custom("unordered set"));
+                }
+                std::mem::swap(&mut previous_element, &mut current_element);
+                first = false;
+            }
+            // Elements with distinct encodings may still compare equal
+            if !elements.insert_element(element) {
+                return Err(de::Error::custom("duplicate set element"));
             }
-
-            previous_element = current_element;
-            hashset.insert(element);
         }
-        Ok(Set::from(hashset))
+        Ok(elements)
     }
 }
 
@@ -446,7 +1315,72 @@ where
     where
         D: Deserializer<'de>,
     {
-        deserializer.deserialize_seq(SetVisitor::<T>::new())
+        deserialize_set::<_, _, HashSet<T>>(deserializer).map(Set::from)
+    }
+}
+
+impl<'de, T> de::Deserialize<'de> for SortedSet<T>
+where
+    T: Deserialize<'de> + Ord + Serialize,
+{
+    fn deserialize<D>(deserializer: D) -> CordResult<SortedSet<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_set::<_, _, BTreeSet<T>>(deserializer).map(SortedSet::from)
+    }
+}
+
+impl<'de, T> de::Deserialize<'de> for PresortedSet<T>
+where
+    T: Deserialize<'de> + Serialize,
+{
+    fn deserialize<D>(deserializer: D) -> CordResult<PresortedSet<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_set::<_, _, Vec<T>>(deserializer).map(PresortedSet)
+    }
+}
+
+struct DeltaSeqVisitor<T> {
+    marker: PhantomData<fn() -> DeltaSeq<T>>,
+}
+
+impl<'de, T: TryFrom<u64>> de::Visitor<'de> for DeltaSeqVisitor<T> {
+    type Value = DeltaSeq<T>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("delta-encoded sequence")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> CordResult<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut elements =
+            Vec::with_capacity(seq.size_hint().unwrap_or(0).min(MAX_PREALLOCATED_ELEMENTS));
+        let mut previous = 0_u64;
+        while let Some(delta) = seq.next_element::<u64>()? {
+            previous = previous
+                .checked_add(delta)
+                .ok_or_else(|| de::Error::custom("delta sequence overflows"))?;
+            let element =
+                T::try_from(previous).map_err(|_| de::Error::custom("delta sequence overflows"))?;
+            elements.push(element);
+        }
+        Ok(DeltaSeq(elements))
+    }
+}
+
+impl<'de, T: TryFrom<u64>> de::Deserialize<'de> for DeltaSeq<T> {
+    fn deserialize<D>(deserializer: D) -> CordResult<DeltaSeq<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(DeltaSeqVisitor {
+            marker: PhantomData,
+        })
     }
 }
 
@@ -463,11 +1397,7 @@ impl de::Visitor<'_> for DateTimeVisitor {
     where
         E: de::Error,
     {
-        let millis = v as i64;
-        let seconds = millis / 1000;
-        let remaining_ns = (millis % 1000) * 1000000;
-
-        let utc_dt = chrono::DateTime::<chrono::Utc>::from_timestamp(seconds, remaining_ns as u32)
+        let utc_dt = Millis::from_units(v as i64)
             .ok_or_else(|| de::Error::custom(format!("timestamp {v} is invalid")))?;
 
         Ok(utc_dt.into())
@@ -483,12 +1413,166 @@ impl<'de> de::Deserialize<'de> for DateTime {
     }
 }
 
+struct DateVisitor;
+
+impl de::Visitor<'_> for DateVisitor {
+    type Value = Date;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("date")
+    }
+
+    fn visit_i32<E>(self, v: i32) -> std::result::Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        chrono::NaiveDate::default()
+            .checked_add_signed(chrono::Duration::days(i64::from(v)))
+            .map(Date::from)
+            .ok_or_else(|| de::Error::custom(format!("date {v} is invalid")))
+    }
+}
+
+impl<'de> de::Deserialize<'de> for Date {
+    fn deserialize<D>(deserializer: D) -> CordResult<Date, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_i32(DateVisitor)
+    }
+}
+
+struct TimeVisitor;
+
+impl de::Visitor<'_> for TimeVisitor {
+    type Value = Time;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("time of day")
+    }
+
+    fn visit_u32<E>(self, v: u32) -> std::result::Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if v >= 86_400_000 {
+            return Err(de::Error::custom(format!("time {v} is out of range")));
+        }
+        chrono::NaiveTime::from_num_seconds_from_midnight_opt(v / 1_000, v % 1_000 * 1_000_000)
+            .map(Time::from)
+            .ok_or_else(|| de::Error::custom(format!("time {v} is invalid")))
+    }
+}
+
+impl<'de> de::Deserialize<'de> for Time {
+    fn deserialize<D>(deserializer: D) -> CordResult<Time, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_u32(TimeVisitor)
+    }
+}
+
+impl<'de> de::Deserialize<'de> for NaiveDateTime {
+    fn deserialize<D>(deserializer: D) -> CordResult<NaiveDateTime, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        DateTime::deserialize(deserializer).map(|datetime| datetime.chrono.naive_utc().into())
+    }
+}
+
+struct DateTimeWithOffsetVisitor;
+
+impl<'de> de::Visitor<'de> for DateTimeWithOffsetVisitor {
+    type Value = DateTimeWithOffset;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("datetime with offset")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> CordResult<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let instant: DateTime = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let minutes: i32 = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+
+        let offset = minutes
+            .checked_mul(60)
+            .and_then(chrono::FixedOffset::east_opt)
+            .ok_or_else(|| de::Error::custom(format!("offset {minutes} is invalid")))?;
+
+        Ok(instant.chrono.with_timezone(&offset).into())
+    }
+}
+
+impl<'de> de::Deserialize<'de> for DateTimeWithOffset {
+    fn deserialize<D>(deserializer: D) -> CordResult<DateTimeWithOffset, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_struct(
+            "DateTimeWithOffset",
+            &["instant", "offset"],
+            DateTimeWithOffsetVisitor,
+        )
+    }
+}
+
+struct TimestampVisitor<P: Precision> {
+    marker: PhantomData<fn() -> P>,
+}
+
+impl<P: Precision> de::Visitor<'_> for TimestampVisitor<P> {
+    type Value = Timestamp<P>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("timestamp")
+    }
+
+    fn visit_u64<E>(self, v: u64) -> std::result::Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let utc_dt = P::from_units(v as i64)
+            .ok_or_else(|| de::Error::custom(format!("timestamp {v} is invalid")))?;
+
+        Ok(utc_dt.into())
+    }
+}
+
+impl<'de, P: Precision> de::Deserialize<'de> for Timestamp<P> {
+    fn deserialize<D>(deserializer: D) -> CordResult<Timestamp<P>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_u64(TimestampVisitor {
+            marker: PhantomData,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::deserialize;
-    use crate::{Bytes, DateTime};
+    use super::{
+        check, deserialize, deserialize_counted, deserialize_owned, deserialize_prefix,
+        deserialize_tolerant, deserialize_versioned, deserialize_with_options, round_trip, Decoder,
+    };
+    use crate::RawBytes;
+    use crate::{BorrowedBytes, Bytes, Date, DateTime, DateTimeWithOffset, Micros, Seconds};
+    use crate::{CordError, IntEncoding, Options, StrEncoding, StructEncoding};
+    use crate::{NaiveDateTime, Time, Timestamp};
     use chrono::Utc;
-    use serde::Deserialize;
+    use serde::{Deserialize, Serialize};
+    use std::borrow::Cow;
+    use std::collections::{BTreeMap, HashMap};
+    use std::rc::Rc;
+    use std::sync::Arc;
 
     #[derive(Debug, Deserialize, PartialEq)]
     enum Enum {
@@ -513,92 +1597,1053 @@ mod tests {
     }
 
     #[test]
-    fn deserialize_booleans() {
-        let false_input: Vec<u8> = vec![0];
-        assert!(!deserialize::<bool>(&false_input).unwrap());
+    fn deserialize_counted_reports_consumed_bytes() {
+        let input: Vec<u8> = vec![4, 116, 101, 115, 116];
+        assert_eq!(
+            deserialize_counted::<String>(&input).unwrap(),
+            (String::from("test"), 5)
+        );
 
-        let true_input: Vec<u8> = vec![1];
-        assert!(deserialize::<bool>(&true_input).unwrap());
+        let input: Vec<u8> = vec![172, 2, 9, 9];
+        assert_eq!(deserialize_counted::<u16>(&input).unwrap(), (300, 2));
     }
 
     #[test]
-    fn deserialize_numbers() {
-        let unsigned_8: Vec<u8> = vec![62];
-        assert_eq!(deserialize::<u8>(&unsigned_8).unwrap(), 62_u8);
-
-        let signed_8: Vec<u8> = vec![59];
-        assert_eq!(deserialize::<i8>(&signed_8).unwrap(), -30_i8);
-
-        let unsigned_32: Vec<u8> = vec![212, 245, 78];
-        assert_eq!(deserialize::<u32>(&unsigned_32).unwrap(), 1293012_u32);
-
-        let signed_32: Vec<u8> = vec![139, 151, 151, 1];
-        assert_eq!(deserialize::<i32>(&signed_32).unwrap(), -1238470);
+    fn deserialize_tolerant_reports_trailing_bytes() {
+        assert_eq!(
+            deserialize_tolerant::<u16>(&[172, 2]).unwrap(),
+            (300, false)
+        );
+        assert_eq!(
+            deserialize_tolerant::<u16>(&[172, 2, 9]).unwrap(),
+            (300, true)
+        );
+        assert_eq!(
+            deserialize_tolerant::<u16>(&[172]).unwrap_err(),
+            CordError::ValidationError("Invalid varint")
+        );
+    }
 
-        let small_unsigned_32: Vec<u8> = vec![12];
-        assert_eq!(deserialize::<u32>(&small_unsigned_32).unwrap(), 12_u32);
+    #[test]
+    fn check_validates_without_returning() {
+        assert!(check::<String>(&[4, 116, 101, 115, 116]).is_ok());
+        assert!(check::<&str>(&[4, 116, 101, 115, 116]).is_ok());
+        assert_eq!(
+            check::<String>(&[4, 116, 101, 115]).unwrap_err(),
+            CordError::ValidationError("Length prefix exceeds input")
+        );
+        assert_eq!(
+            check::<u8>(&[1, 2]).unwrap_err(),
+            CordError::ValidationError("Unexpected trailing bytes")
+        );
     }
 
     #[test]
-    fn deserialize_strings() {
-        let string: Vec<u8> = vec![4, 116, 101, 115, 116];
-        assert_eq!(deserialize::<String>(&string).unwrap(), "test");
+    fn deserialize_owned_outlives_input() {
+        let decoded: String = {
+            let input: Vec<u8> = vec![4, 116, 101, 115, 116];
+            deserialize_owned(&input).unwrap()
+        };
+        assert_eq!(decoded, "test");
     }
 
     #[test]
-    fn deserialize_empty_strings() {
-        let string: Vec<u8> = vec![0];
-        assert_eq!(deserialize::<String>(&string).unwrap(), "");
+    fn round_trip_returns_decoded_value() {
+        assert_eq!(
+            round_trip(&(String::from("test"), 300_u16)).unwrap(),
+            (String::from("test"), 300)
+        );
+        assert_eq!(
+            round_trip(&'a').unwrap_err(),
+            CordError::NotSupported("char")
+        );
+    }
+
+    #[test]
+    fn deserialize_with_decoder() {
+        let input: Vec<u8> = vec![4, 116, 101, 115, 116, 172, 2, 9];
+        let mut decoder = Decoder::new(&input);
+        assert_eq!(decoder.value::<&str>().unwrap(), "test");
+        assert_eq!(decoder.value::<u16>().unwrap(), 300);
+        assert_eq!(decoder.remaining(), [9]);
+
+        assert!(decoder.value::<String>().is_err());
+        assert_eq!(decoder.remaining(), [9]);
+        assert_eq!(decoder.value::<u8>().unwrap(), 9);
+        assert!(decoder.is_empty());
+
+        let mut decoder = Decoder::with_options(&[0, 7, 1], Options::order_preserving());
+        assert_eq!(decoder.value::<u16>().unwrap(), 7);
+        assert!(decoder.value::<bool>().unwrap());
+        assert!(decoder.is_empty());
+    }
+
+    #[test]
+    fn deserialize_prefix_ignores_padding() {
+        let input: Vec<u8> = vec![4, 116, 101, 115, 116, 0, 0, 0];
+        assert_eq!(deserialize_prefix::<String>(&input).unwrap(), "test");
+        assert!(deserialize::<String>(&input).is_err());
+
+        let input: Vec<u8> = vec![4, 116, 101];
+        assert!(deserialize_prefix::<String>(&input).is_err());
+    }
+
+    #[test]
+    fn deserialize_versioned_checks_version() {
+        let input: Vec<u8> = vec![1, 4, 116, 101, 115, 116];
+        assert_eq!(deserialize_versioned::<&str>(&input).unwrap(), "test");
+        assert_eq!(
+            deserialize_versioned::<&str>(&[2, 4, 116, 101, 115, 116]).unwrap_err(),
+            CordError::ValidationError("unsupported format version")
+        );
+        assert_eq!(
+            deserialize_versioned::<()>(&[]).unwrap_err(),
+            CordError::ValidationError("Unexpected end of stream")
+        );
+        assert!(deserialize_versioned::<&str>(&[1, 4, 116]).is_err());
+    }
+
+    #[test]
+    fn deserialize_lenient_booleans() {
+        assert_eq!(
+            deserialize::<bool>(&[255]).unwrap_err(),
+            CordError::ValidationError("Invalid boolean variant")
+        );
+        assert_eq!(
+            deserialize::<Option<u8>>(&[255, 7]).unwrap_err(),
+            CordError::ValidationError("Invalid option variant")
+        );
+
+        let options = Options::new().lenient_bool();
+        assert!(deserialize_with_options::<bool>(&[255], options).unwrap());
+        assert!(!deserialize_with_options::<bool>(&[0], options).unwrap());
+        assert_eq!(
+            deserialize_with_options::<Option<u8>>(&[255, 7], options).unwrap(),
+            Some(7)
+        );
+        assert_eq!(
+            deserialize_with_options::<Option<u8>>(&[0], options).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn deserialize_booleans() {
+        let false_input: Vec<u8> = vec![0];
+        assert!(!deserialize::<bool>(&false_input).unwrap());
+
+        let true_input: Vec<u8> = vec![1];
+        assert!(deserialize::<bool>(&true_input).unwrap());
+    }
+
+    #[test]
+    fn deserialize_numbers() {
+        let unsigned_8: Vec<u8> = vec![62];
+        assert_eq!(deserialize::<u8>(&unsigned_8).unwrap(), 62_u8);
+
+        let signed_8: Vec<u8> = vec![59];
+        assert_eq!(deserialize::<i8>(&signed_8).unwrap(), -30_i8);
+
+        let unsigned_32: Vec<u8> = vec![212, 245, 78];
+        assert_eq!(deserialize::<u32>(&unsigned_32).unwrap(), 1293012_u32);
+
+        let signed_32: Vec<u8> = vec![139, 151, 151, 1];
+        assert_eq!(deserialize::<i32>(&signed_32).unwrap(), -1238470);
+
+        let small_unsigned_32: Vec<u8> = vec![12];
+        assert_eq!(deserialize::<u32>(&small_unsigned_32).unwrap(), 12_u32);
+    }
+
+    #[test]
+    fn deserialize_non_canonical_varints() {
+        for input in [&[0x80, 0x00][..], &[0x8C, 0x80, 0x00], &[0x80, 0x02]] {
+            assert_eq!(
+                deserialize::<u8>(input).unwrap_err(),
+                CordError::ValidationError("Non-canonical varint")
+            );
+        }
+        assert_eq!(
+            deserialize::<u128>(&[0x81, 0x00]).unwrap_err(),
+            CordError::ValidationError("Non-canonical varint")
+        );
+    }
+
+    #[test]
+    fn deserialize_numbers_as_fixed_big_endian() {
+        let options = Options::new().int_encoding(IntEncoding::FixedBigEndian);
+
+        assert_eq!(
+            deserialize_with_options::<u16>(&[1, 44], options).unwrap(),
+            300
+        );
+        assert_eq!(
+            deserialize_with_options::<i8>(&[0x7f], options).unwrap(),
+            -1
+        );
+        assert_eq!(
+            deserialize_with_options::<i32>(&[0x7f, 0xff, 0xff, 0xfe], options).unwrap(),
+            -2
+        );
+        assert!(deserialize_with_options::<u32>(&[0, 0, 1], options).is_err());
+    }
+
+    #[test]
+    fn deserialize_fixed_big_endian_independent_of_host() {
+        let options = Options::new().int_encoding(IntEncoding::FixedBigEndian);
+
+        assert_eq!(
+            deserialize_with_options::<u32>(&0x0102_0304_u32.to_be_bytes(), options).unwrap(),
+            0x0102_0304
+        );
+        assert_eq!(
+            deserialize_with_options::<u64>(&0x0102_0304_0506_0708_u64.to_be_bytes(), options)
+                .unwrap(),
+            0x0102_0304_0506_0708
+        );
+        assert_eq!(
+            deserialize_with_options::<i16>(
+                &((-0x0102_i16 as u16) ^ 0x8000).to_be_bytes(),
+                options
+            )
+            .unwrap(),
+            -0x0102
+        );
+        assert_eq!(
+            deserialize_with_options::<i64>(
+                &((-0x0102_0304_0506_0708_i64 as u64) ^ 0x8000_0000_0000_0000).to_be_bytes(),
+                options
+            )
+            .unwrap(),
+            -0x0102_0304_0506_0708
+        );
+    }
+
+    #[test]
+    fn deserialize_strings() {
+        let string: Vec<u8> = vec![4, 116, 101, 115, 116];
+        assert_eq!(deserialize::<String>(&string).unwrap(), "test");
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Key {
+        tenant: u16,
+        path: String,
+        versions: Vec<i32>,
+    }
+
+    #[test]
+    fn deserialize_order_preserving() {
+        let input: Vec<u8> = vec![
+            0, 7, 97, 0, 0xff, 98, 0, 1, 1, 0x7f, 0xff, 0xff, 0xff, 1, 0x80, 0, 0, 2, 0,
+        ];
+
+        assert_eq!(
+            deserialize_with_options::<Key>(&input, Options::order_preserving()).unwrap(),
+            Key {
+                tenant: 7,
+                path: String::from("a\0b"),
+                versions: vec![-1, 2],
+            }
+        );
+    }
+
+    #[test]
+    fn deserialize_order_preserving_borrows_unescaped_strings() {
+        let input: Vec<u8> = vec![116, 101, 115, 116, 0, 1];
+        assert_eq!(
+            deserialize_with_options::<&str>(&input, Options::order_preserving()).unwrap(),
+            "test"
+        );
+    }
+
+    #[test]
+    fn deserialize_order_preserving_invalid_escape() {
+        let options = Options::order_preserving();
+        assert!(deserialize_with_options::<String>(&[97, 0, 2], options).is_err());
+        assert!(deserialize_with_options::<String>(&[97, 98], options).is_err());
+        assert!(deserialize_with_options::<Vec<u8>>(&[1, 5, 2], options).is_err());
+    }
+
+    #[test]
+    fn deserialize_nul_terminated_strings() {
+        let options = Options::new().str_encoding(StrEncoding::NulTerminated);
+
+        assert_eq!(
+            deserialize_with_options::<&str>(&[116, 101, 115, 116, 0], options).unwrap(),
+            "test"
+        );
+        assert_eq!(
+            deserialize_with_options::<String>(&[0], options).unwrap(),
+            ""
+        );
+        assert!(deserialize_with_options::<String>(&[116, 101], options).is_err());
+        assert!(deserialize_with_options::<String>(&[116, 0, 101, 0], options).is_err());
+    }
+
+    #[test]
+    fn deserialize_length_prefix_exceeding_input() {
+        let input: Vec<u8> = vec![5, 116, 101, 115, 116];
+        assert_eq!(
+            deserialize::<String>(&input).unwrap_err(),
+            CordError::ValidationError("Length prefix exceeds input")
+        );
+
+        let input: Vec<u8> = vec![255, 255, 255, 255, 255, 255, 255, 255, 255, 1, 0];
+        assert_eq!(
+            deserialize::<Bytes>(&input).unwrap_err(),
+            CordError::ValidationError("Length prefix exceeds input")
+        );
+    }
+
+    #[test]
+    fn deserialize_zero_sized_sequence_beyond_input() {
+        let input: Vec<u8> = vec![200, 1];
+        assert_eq!(deserialize::<Vec<()>>(&input).unwrap().len(), 200);
+    }
+
+    #[test]
+    fn deserialize_empty_strings() {
+        let string: Vec<u8> = vec![0];
+        assert_eq!(deserialize::<String>(&string).unwrap(), "");
     }
 
     #[test]
     fn deserialize_large_bytearrays() {
         let length = 300;
 
-        let mut input: Vec<u8> = vec![172, 2];
-        input.extend(vec![b'0'; length]);
+        let mut input: Vec<u8> = vec![172, 2];
+        input.extend(vec![b'0'; length]);
+
+        assert_eq!(deserialize::<Vec<u8>>(&input).unwrap(), vec![b'0'; length]);
+    }
+
+    #[test]
+    fn deserialize_empty_bytearrays() {
+        let input: Vec<u8> = vec![0];
+        assert_eq!(deserialize::<Vec<u8>>(&input).unwrap(), Vec::<u8>::new());
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Inbox {
+        owner: u8,
+        messages: Vec<String>,
+        labels: HashMap<String, u8>,
+        attachment: Bytes,
+    }
+
+    #[test]
+    fn deserialize_empty_collections() {
+        assert!(deserialize::<crate::Set<u8>>(&[0])
+            .unwrap()
+            .hashset
+            .is_empty());
+        assert!(deserialize::<HashMap<u8, u8>>(&[0]).unwrap().is_empty());
+        assert!(deserialize::<Bytes>(&[0]).unwrap().as_slice().is_empty());
+        assert_eq!(
+            deserialize::<Inbox>(&[7, 0, 0, 0]).unwrap(),
+            Inbox {
+                owner: 7,
+                messages: Vec::new(),
+                labels: HashMap::new(),
+                attachment: Bytes::from(Vec::new()),
+            }
+        );
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    enum Literal {
+        Unit,
+        Int(u32),
+        Text(String),
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    enum Expr {
+        Literal(Literal),
+        Negate(Box<Expr>),
+        Binary {
+            operator: Literal,
+            operands: Vec<Expr>,
+        },
+    }
+
+    #[test]
+    fn deserialize_nested_enums() {
+        let value = Expr::Binary {
+            operator: Literal::Text(String::from("+")),
+            operands: vec![
+                Expr::Literal(Literal::Int(300)),
+                Expr::Negate(Box::new(Expr::Literal(Literal::Unit))),
+            ],
+        };
+        let input: Vec<u8> = vec![2, 2, 1, 43, 2, 0, 1, 172, 2, 1, 0, 0];
+        assert_eq!(crate::serialize(&value).unwrap(), input);
+        assert_eq!(deserialize::<Expr>(&input).unwrap(), value);
+
+        let options = Options::new().struct_encoding(StructEncoding::LengthPrefixed);
+        let input: Vec<u8> = vec![2, 2, 2, 1, 43, 2, 0, 1, 172, 2, 1, 0, 0];
+        assert_eq!(
+            crate::serialize_with_options(&value, options).unwrap(),
+            input
+        );
+        assert_eq!(
+            deserialize_with_options::<Expr>(&input, options).unwrap(),
+            value
+        );
+
+        let input: Vec<u8> = vec![1, 0, 3];
+        assert!(deserialize::<Expr>(&input).is_err());
+    }
+
+    #[test]
+    fn deserialize_tuple_matrix() {
+        let one = (300_u16,);
+        let input = crate::serialize(&one).unwrap();
+        assert_eq!(input, [1, 172, 2]);
+        assert_eq!(deserialize::<(u16,)>(&input).unwrap(), one);
+
+        let two = (String::from("a"), -1_i8);
+        let input = crate::serialize(&two).unwrap();
+        assert_eq!(input, [2, 1, 97, 1]);
+        assert_eq!(deserialize::<(String, i8)>(&input).unwrap(), two);
+
+        type Eight = (u8, u16, u32, u64, i8, i16, bool, Option<u8>);
+        let eight: Eight = (1, 2, 3, 4, -1, -2, true, None);
+        let input = crate::serialize(&eight).unwrap();
+        assert_eq!(input, [8, 1, 2, 3, 4, 1, 3, 1, 0]);
+        assert_eq!(deserialize::<Eight>(&input).unwrap(), eight);
+
+        type Sixteen = (
+            u8,
+            String,
+            u16,
+            Vec<u8>,
+            u32,
+            bool,
+            i64,
+            Option<String>,
+            u8,
+            (u8, u8),
+            u8,
+            u8,
+            u8,
+            u8,
+            u8,
+            u64,
+        );
+        let sixteen: Sixteen = (
+            1,
+            String::from("b"),
+            300,
+            vec![5, 6],
+            7,
+            false,
+            -3,
+            Some(String::from("c")),
+            9,
+            (10, 11),
+            12,
+            13,
+            14,
+            15,
+            16,
+            17,
+        );
+        let input = crate::serialize(&sixteen).unwrap();
+        assert_eq!(
+            input,
+            [
+                16, 1, 1, 98, 172, 2, 2, 5, 6, 7, 0, 5, 1, 1, 99, 9, 2, 10, 11, 12, 13, 14, 15, 16,
+                17
+            ]
+        );
+        // std implements neither `PartialEq` nor `Debug` for tuples above twelve elements
+        let decoded = deserialize::<Sixteen>(&input).unwrap();
+        assert_eq!(crate::serialize(&decoded).unwrap(), input);
+        assert_eq!(
+            (decoded.1, decoded.7, decoded.15),
+            (sixteen.1, sixteen.7, 17)
+        );
+
+        let mut extended = input.clone();
+        extended.push(0);
+        assert!(deserialize::<Sixteen>(&extended).is_err());
+        assert!(deserialize::<Sixteen>(&input[..input.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn deserialize_nested_sequences() {
+        let value: Vec<Vec<Vec<u32>>> = vec![vec![], vec![vec![], vec![1, 300]], vec![vec![2]]];
+        let input: Vec<u8> = vec![3, 0, 2, 0, 2, 1, 172, 2, 1, 1, 2];
+        assert_eq!(crate::serialize(&value).unwrap(), input);
+        assert_eq!(deserialize::<Vec<Vec<Vec<u32>>>>(&input).unwrap(), value);
+
+        let options = Options::order_preserving().int_encoding(IntEncoding::Varint);
+        let input: Vec<u8> = vec![1, 0, 1, 1, 0, 1, 1, 1, 1, 172, 2, 0, 0, 1, 1, 1, 2, 0, 0, 0];
+        assert_eq!(
+            crate::serialize_with_options(&value, options).unwrap(),
+            input
+        );
+        assert_eq!(
+            deserialize_with_options::<Vec<Vec<Vec<u32>>>>(&input, options).unwrap(),
+            value
+        );
+
+        let truncated: Vec<u8> = vec![3, 0, 2, 0, 2, 1, 172, 2, 1];
+        assert!(deserialize::<Vec<Vec<Vec<u32>>>>(&truncated).is_err());
+    }
+
+    #[test]
+    fn deserialize_immutable_slices() {
+        let input: Vec<u8> = vec![3, 1, 172, 2, 3];
+        let expected: &[u16] = &[1, 300, 3];
+        assert_eq!(&*deserialize::<Box<[u16]>>(&input).unwrap(), expected);
+        assert_eq!(&*deserialize::<Rc<[u16]>>(&input).unwrap(), expected);
+        assert_eq!(&*deserialize::<Arc<[u16]>>(&input).unwrap(), expected);
+
+        let input: Vec<u8> = vec![2, 1, 97, 1, 98];
+        assert_eq!(
+            &*deserialize::<Box<[String]>>(&input).unwrap(),
+            &[String::from("a"), String::from("b")]
+        );
+        assert!(deserialize::<Box<[u16]>>(&[0]).unwrap().is_empty());
+
+        let input: Vec<u8> = vec![255, 255, 255, 255, 15, 1];
+        assert!(deserialize::<Box<[u16]>>(&input).is_err());
+        assert!(deserialize::<Arc<[u16]>>(&input).is_err());
+    }
+
+    #[derive(Deserialize)]
+    struct Request<'a> {
+        path: &'a str,
+        #[serde(borrow)]
+        query: Cow<'a, str>,
+        #[serde(borrow)]
+        body: BorrowedBytes<'a>,
+    }
+
+    #[test]
+    fn deserialize_borrowed_struct_without_allocating() {
+        let input: Vec<u8> = vec![1, 47, 3, 97, 61, 49, 2, 0, 1];
+        let request = deserialize::<Request>(&input).unwrap();
+        assert_eq!(request.path.as_ptr(), input[1..].as_ptr());
+        assert!(matches!(request.query, Cow::Borrowed("a=1")));
+        assert_eq!(request.query.as_ptr(), input[3..].as_ptr());
+        assert!(request.body.is_borrowed());
+        assert_eq!(request.body.as_slice().as_ptr(), input[7..].as_ptr());
+    }
+
+    #[test]
+    fn deserialize_fixed_length_bytes() {
+        let input: Vec<u8> = vec![67, 79, 82, 68, 172, 2];
+        assert_eq!(
+            deserialize::<(RawBytes<4>, u16)>(&[2, 67, 79, 82, 68, 172, 2]).unwrap(),
+            (RawBytes(*b"CORD"), 300)
+        );
+        assert_eq!(
+            deserialize::<RawBytes<4>>(&input[..4]).unwrap(),
+            RawBytes(*b"CORD")
+        );
+        assert_eq!(
+            deserialize::<RawBytes<4>>(&input[..3]).unwrap_err(),
+            CordError::ValidationError("Unexpected end of stream")
+        );
+        assert!(deserialize::<RawBytes<3>>(&input[..4]).is_err());
+        assert_eq!(
+            deserialize::<Option<RawBytes<2>>>(&[1, 0, 200]).unwrap(),
+            Some(RawBytes([0, 200]))
+        );
+    }
+
+    #[test]
+    fn deserialize_borrowed_bytes() {
+        let input: Vec<u8> = vec![3, 0, 1, 2];
+        let bytes = deserialize::<BorrowedBytes>(&input).unwrap();
+        assert!(bytes.is_borrowed());
+        assert_eq!(bytes.as_slice().as_ptr(), input[1..].as_ptr());
+        assert_eq!(crate::serialize(&bytes).unwrap(), input);
+        assert_eq!(bytes.into_owned(), Bytes::from(vec![0, 1, 2]));
+
+        let options = Options::order_preserving();
+        let input: Vec<u8> = vec![1, 0, 255, 2, 0, 1];
+        let bytes = deserialize_with_options::<BorrowedBytes>(&input, options).unwrap();
+        assert!(!bytes.is_borrowed());
+        assert_eq!(bytes.as_slice(), [1, 0, 2]);
+
+        let input: Vec<u8> = vec![1, 2, 0, 1];
+        let bytes = deserialize_with_options::<BorrowedBytes>(&input, options).unwrap();
+        assert!(bytes.is_borrowed());
+    }
+
+    #[test]
+    fn deserialize_bytes() {
+        let input: Vec<u8> = vec![3, 0, 1, 2];
+        assert_eq!(
+            deserialize::<Bytes>(&input).unwrap(),
+            Bytes::from(vec![0, 1, 2])
+        );
+    }
+
+    #[test]
+    fn deserialize_tuple() {
+        assert_eq!(deserialize::<[u8; 3]>(&[3, 0, 1, 2]).unwrap(), [0, 1, 2]);
+        assert_eq!(
+            deserialize::<(u8, String)>(&[2, 7, 1, 97]).unwrap(),
+            (7, String::from("a"))
+        );
+        assert_eq!(
+            deserialize::<[u8; 3]>(&[2, 0, 1]).unwrap_err(),
+            CordError::ValidationError("Unexpected tuple length")
+        );
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct LargeArrays {
+        #[serde(with = "crate::array")]
+        bytes: [u8; 64],
+        #[serde(with = "crate::array")]
+        words: [u16; 128],
+    }
+
+    #[test]
+    fn deserialize_large_arrays() {
+        let mut input: Vec<u8> = vec![64];
+        input.extend((0..64).map(|i| i as u8));
+        input.extend_from_slice(&[128, 1]);
+        input.extend(vec![0; 128]);
+
+        let decoded = deserialize::<LargeArrays>(&input).unwrap();
+        assert_eq!(decoded.bytes[63], 63);
+        assert_eq!(decoded.words[0], 0);
+        assert_eq!(decoded.words.len(), 128);
+
+        assert!(deserialize::<LargeArrays>(&input[..70]).is_err());
+        assert!(deserialize::<LargeArrays>(&input[..1]).is_err());
+    }
+
+    #[test]
+    fn deserialize_datetime() {
+        let input: Vec<u8> = vec![192, 172, 251, 129, 176, 49];
+        let expected_datetime: DateTime =
+            chrono::DateTime::parse_from_rfc3339("2023-10-05T14:30:00.000Z")
+                .unwrap()
+                .with_timezone(&Utc)
+                .into();
+
+        assert_eq!(deserialize::<DateTime>(&input).unwrap(), expected_datetime);
+    }
+
+    #[test]
+    fn deserialize_date() {
+        assert_eq!(
+            deserialize::<Date>(&[230, 178, 2]).unwrap().chrono,
+            chrono::NaiveDate::from_ymd_opt(2023, 10, 5).unwrap()
+        );
+        assert_eq!(
+            deserialize::<Date>(&[1]).unwrap().chrono,
+            chrono::NaiveDate::from_ymd_opt(1969, 12, 31).unwrap()
+        );
+        assert!(deserialize::<Date>(&[254, 255, 255, 255, 15]).is_err());
+    }
+
+    #[test]
+    fn deserialize_time() {
+        assert_eq!(
+            deserialize::<Time>(&[186, 134, 242, 24]).unwrap().chrono,
+            chrono::NaiveTime::from_hms_milli_opt(14, 30, 0, 250).unwrap()
+        );
+        assert_eq!(
+            deserialize::<Time>(&[128, 184, 153, 41]).unwrap_err(),
+            CordError::DeserializationError(String::from("time 86400000 is out of range"))
+        );
+    }
+
+    #[test]
+    fn deserialize_naive_datetime() {
+        let input: Vec<u8> = vec![192, 172, 251, 129, 176, 49];
+        assert_eq!(
+            deserialize::<NaiveDateTime>(&input).unwrap().chrono,
+            chrono::NaiveDate::from_ymd_opt(2023, 10, 5)
+                .unwrap()
+                .and_hms_opt(14, 30, 0)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn deserialize_timestamp_precisions() {
+        let input: Vec<u8> = vec![232, 144, 251, 168, 6];
+        let expected = chrono::DateTime::parse_from_rfc3339("2023-10-05T14:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            deserialize::<Timestamp<Seconds>>(&input).unwrap().chrono,
+            expected
+        );
+
+        let input: Vec<u8> = vec![192, 172, 251, 129, 176, 49];
+        assert_eq!(
+            deserialize::<Timestamp<Micros>>(&input).unwrap().chrono,
+            chrono::DateTime::parse_from_rfc3339("1970-01-20T15:15:16.200Z")
+                .unwrap()
+                .with_timezone(&Utc)
+        );
+    }
+
+    #[test]
+    fn deserialize_datetime_with_offset() {
+        let input: Vec<u8> = vec![192, 172, 251, 129, 176, 49, 240, 1];
+        let decoded = deserialize::<DateTimeWithOffset>(&input).unwrap();
+
+        assert_eq!(decoded.chrono.to_rfc3339(), "2023-10-05T16:30:00+02:00");
+        assert_eq!(decoded.chrono.offset().local_minus_utc(), 7200);
+    }
+
+    #[test]
+    fn deserialize_datetime_with_invalid_offset() {
+        let input: Vec<u8> = vec![192, 172, 251, 129, 176, 49, 128, 200, 1];
+        assert!(deserialize::<DateTimeWithOffset>(&input).is_err());
+    }
+
+    #[test]
+    fn deserialize_set() {
+        let input: Vec<u8> = vec![
+            7, 1, 97, 1, 98, 1, 99, 1, 100, 1, 101, 1, 102, 4, 116, 101, 115, 116,
+        ];
+        let expected: crate::Set<String> = vec!["a", "b", "c", "d", "e", "f", "test"]
+            .into_iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        assert_eq!(deserialize::<crate::Set<String>>(&input).unwrap(), expected);
+    }
+
+    #[test]
+    fn deserialize_set_orders_by_encoded_bytes() {
+        let input: Vec<u8> = vec![3, 1, 98, 1, 122, 2, 97, 97];
+        assert_eq!(
+            deserialize::<crate::Set<String>>(&input).unwrap(),
+            vec!["aa", "z", "b"]
+                .into_iter()
+                .map(|s| s.to_string())
+                .collect()
+        );
+
+        let input: Vec<u8> = vec![3, 2, 97, 97, 1, 98, 1, 122];
+        assert!(deserialize::<crate::Set<String>>(&input).is_err());
+    }
+
+    thread_local! {
+        static ENCODED: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    // Counts how many times it is encoded
+    #[derive(Debug, Deserialize, PartialEq, Eq, Hash)]
+    struct Probe(u8);
+
+    impl Serialize for Probe {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            ENCODED.with(|encoded| encoded.set(encoded.get() + 1));
+            serializer.serialize_u8(self.0)
+        }
+    }
+
+    #[test]
+    fn deserialize_set_compares_input() {
+        let input: Vec<u8> = vec![3, 1, 2, 3];
+        ENCODED.with(|encoded| encoded.set(0));
+        assert!(deserialize::<crate::Set<Probe>>(&input).is_ok());
+        assert_eq!(ENCODED.with(|encoded| encoded.get()), 0);
+
+        // Under options other than the default, elements are encoded to check their order
+        let options = Options::new().lenient_bool();
+        assert!(deserialize_with_options::<crate::Set<Probe>>(&input, options).is_ok());
+        assert_eq!(ENCODED.with(|encoded| encoded.get()), 3);
+
+        let input: Vec<u8> = vec![3, 1, 3, 2];
+        assert!(deserialize::<crate::Set<Probe>>(&input).is_err());
+        assert!(deserialize_with_options::<crate::Set<Probe>>(&input, options).is_err());
+
+        let input: Vec<u8> = vec![2, 1, 1];
+        assert!(deserialize::<crate::Set<Probe>>(&input).is_err());
+        assert!(deserialize_with_options::<crate::Set<Probe>>(&input, options).is_err());
+    }
+
+    #[test]
+    fn deserialize_sorted_set() {
+        let input: Vec<u8> = vec![3, 1, 98, 1, 122, 2, 97, 97];
+        let set = deserialize::<crate::SortedSet<String>>(&input).unwrap();
+        assert_eq!(Vec::from(set), vec!["aa", "b", "z"]);
+
+        let input: Vec<u8> = vec![3, 2, 97, 97, 1, 98, 1, 122];
+        assert!(deserialize::<crate::SortedSet<String>>(&input).is_err());
+    }
+
+    #[test]
+    fn deserialize_presorted_set() {
+        let input: Vec<u8> = vec![3, 1, 98, 1, 122, 2, 97, 97];
+        assert_eq!(
+            deserialize::<crate::PresortedSet<String>>(&input).unwrap(),
+            crate::PresortedSet(vec![
+                String::from("b"),
+                String::from("z"),
+                String::from("aa")
+            ])
+        );
+
+        let input: Vec<u8> = vec![3, 2, 97, 97, 1, 98, 1, 122];
+        assert!(deserialize::<crate::PresortedSet<String>>(&input).is_err());
+    }
+
+    #[test]
+    fn deserialize_presorted_set_with_duplicate() {
+        let input: Vec<u8> = vec![2, 1, 98, 1, 98];
+        let err = deserialize::<crate::PresortedSet<String>>(&input).unwrap_err();
+        assert_eq!(
+            err.root_cause(),
+            &CordError::DeserializationError(String::from("unordered set"))
+        );
+    }
+
+    #[test]
+    fn deserialize_presorted_set_with_overlong_varint() {
+        let err = deserialize::<crate::PresortedSet<u32>>(&[2, 1, 0x81, 0x00]).unwrap_err();
+        assert_eq!(
+            err.root_cause(),
+            &CordError::ValidationError("Non-canonical varint")
+        );
+        let err =
+            deserialize::<crate::PresortedSet<u32>>(&[2, 0x80, 0x01, 0x85, 0x00]).unwrap_err();
+        assert_eq!(
+            err.root_cause(),
+            &CordError::ValidationError("Non-canonical varint")
+        );
+    }
+
+    #[test]
+    fn deserialize_delta_seq() {
+        let input: Vec<u8> = vec![4, 232, 7, 1, 0, 2];
+        assert_eq!(
+            deserialize::<crate::DeltaSeq<u32>>(&input).unwrap(),
+            crate::DeltaSeq(vec![1000, 1001, 1001, 1003])
+        );
 
-        assert_eq!(deserialize::<Vec<u8>>(&input).unwrap(), vec![b'0'; length]);
+        let input: Vec<u8> = vec![2, 255, 1, 1];
+        assert_eq!(
+            deserialize::<crate::DeltaSeq<u8>>(&input).unwrap_err(),
+            CordError::DeserializationError(String::from("delta sequence overflows"))
+        );
     }
 
     #[test]
-    fn deserialize_empty_bytearrays() {
-        let input: Vec<u8> = vec![0];
-        assert_eq!(deserialize::<Vec<u8>>(&input).unwrap(), vec![]);
+    fn deserialize_collection_len_limit() {
+        let options = Options::new().max_collection_len(2);
+        let input: Vec<u8> = vec![2, 1, 97, 1, 98];
+        assert_eq!(
+            deserialize_with_options::<crate::Set<String>>(&input, options)
+                .unwrap()
+                .hashset
+                .len(),
+            2
+        );
+
+        let input: Vec<u8> = vec![3, 1, 97, 1, 98, 1, 99];
+        assert_eq!(
+            deserialize_with_options::<crate::Set<String>>(&input, options).unwrap_err(),
+            CordError::ValidationError("Collection length exceeds limit")
+        );
+
+        let options = Options::order_preserving().max_collection_len(2);
+        let input: Vec<u8> = vec![1, 1, 1, 2, 1, 3, 0];
+        assert_eq!(
+            deserialize_with_options::<Vec<u8>>(&input, options).unwrap_err(),
+            CordError::ValidationError("Collection length exceeds limit")
+        );
     }
 
     #[test]
-    fn deserialize_bytes() {
-        let input: Vec<u8> = vec![3, 0, 1, 2];
+    fn deserialize_chunked_sequences() {
+        let options = Options::new().streaming_seq();
         assert_eq!(
-            deserialize::<Bytes>(&input).unwrap(),
-            Bytes::from(vec![0, 1, 2])
+            deserialize_with_options::<Vec<u16>>(&[2, 2, 172, 2, 1, 5, 0], options).unwrap(),
+            [2, 300, 5]
+        );
+        assert!(deserialize_with_options::<Vec<u16>>(&[0], options)
+            .unwrap()
+            .is_empty());
+        assert!(deserialize_with_options::<Vec<u16>>(&[2, 2, 172, 2], options).is_err());
+
+        let input: Vec<u8> = vec![1, 1, 4, 1, 2, 3, 0];
+        let map = deserialize_with_options::<BTreeMap<u8, u8>>(&input, options).unwrap();
+        assert_eq!(map.into_iter().collect::<Vec<_>>(), [(1, 4), (2, 3)]);
+        assert!(deserialize_with_options::<BTreeMap<u8, u8>>(&[0], options)
+            .unwrap()
+            .is_empty());
+
+        let options = options.max_collection_len(2);
+        assert_eq!(
+            deserialize_with_options::<Vec<u8>>(&[2, 1, 2, 1, 3, 0], options).unwrap_err(),
+            CordError::ValidationError("Collection length exceeds limit")
         );
     }
 
     #[test]
-    fn deserialize_datetime() {
-        let input: Vec<u8> = vec![192, 172, 251, 129, 176, 49];
-        let expected_datetime: DateTime =
-            chrono::DateTime::parse_from_rfc3339("2023-10-05T14:30:00.000Z")
-                .unwrap()
-                .with_timezone(&Utc)
-                .into();
+    fn deserialize_str_len_limit() {
+        let options = Options::new().max_str_len(3);
+        assert_eq!(
+            deserialize_with_options::<String>(&[3, 97, 98, 99], options).unwrap(),
+            "abc"
+        );
+        assert_eq!(
+            deserialize_with_options::<String>(&[4, 97, 98, 99, 100], options).unwrap_err(),
+            CordError::ValidationError("string too long")
+        );
+        assert_eq!(
+            deserialize_with_options::<Vec<u8>>(&[4, 97, 98, 99, 100], options).unwrap(),
+            b"abcd"
+        );
 
-        assert_eq!(deserialize::<DateTime>(&input).unwrap(), expected_datetime);
+        let options = Options::order_preserving().max_str_len(3);
+        assert_eq!(
+            deserialize_with_options::<String>(&[97, 98, 99, 100, 0, 1], options).unwrap_err(),
+            CordError::ValidationError("string too long")
+        );
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Labelled {
+        label: String,
+        labels: BTreeMap<String, String>,
     }
 
     #[test]
-    fn deserialize_set() {
-        let input: Vec<u8> = vec![
-            7, 1, 97, 1, 98, 1, 99, 1, 100, 1, 101, 1, 102, 4, 116, 101, 115, 116,
-        ];
-        let expected: crate::Set<String> = vec!["a", "b", "c", "d", "e", "f", "test"]
+    fn deserialize_string_dictionary() {
+        let options = Options::new().string_dictionary();
+        let input: Vec<u8> = vec![4, 0, 2, 97, 98, 0, 2, 99, 100, 1, 1];
+        assert_eq!(
+            deserialize_with_options::<Vec<&str>>(&input, options).unwrap(),
+            vec!["ab", "cd", "ab", "ab"]
+        );
+
+        let input: Vec<u8> = vec![0, 2, 97, 98, 1, 2, 97, 98, 2, 97, 98];
+        assert_eq!(
+            deserialize_with_options::<Labelled>(&input, options).unwrap(),
+            Labelled {
+                label: String::from("ab"),
+                labels: BTreeMap::from([(String::from("ab"), String::from("ab"))]),
+            }
+        );
+
+        let input: Vec<u8> = vec![2, 0, 1, 97, 2];
+        assert_eq!(
+            deserialize_with_options::<Vec<String>>(&input, options).unwrap_err(),
+            CordError::ValidationError("Invalid dictionary reference")
+        );
+    }
+
+    #[test]
+    fn deserialize_string_dictionary_repeated_literal() {
+        let options = Options::new().string_dictionary();
+        let input: Vec<u8> = vec![2, 0, 2, 97, 98, 0, 2, 97, 98];
+        assert_eq!(
+            deserialize_with_options::<Vec<String>>(&input, options).unwrap_err(),
+            CordError::ValidationError("Repeated dictionary literal")
+        );
+    }
+
+    #[test]
+    fn deserialize_string_dictionary_expansion_limit() {
+        let options = Options::new()
+            .string_dictionary()
+            .max_dictionary_expansion(4);
+        let input: Vec<u8> = vec![3, 0, 2, 97, 98, 1, 1];
+        assert_eq!(
+            deserialize_with_options::<Vec<String>>(&input, options).unwrap(),
+            vec!["ab", "ab", "ab"]
+        );
+
+        let input: Vec<u8> = vec![4, 0, 2, 97, 98, 1, 1, 1];
+        assert_eq!(
+            deserialize_with_options::<Vec<String>>(&input, options).unwrap_err(),
+            CordError::ValidationError("Dictionary expansion exceeds limit")
+        );
+    }
+
+    #[test]
+    fn deserialize_set_with_huge_length_prefix() {
+        let input: Vec<u8> = vec![255, 255, 255, 255, 15, 1, 97];
+        assert!(deserialize::<crate::Set<String>>(&input).is_err());
+    }
+
+    #[test]
+    fn deserialize_map() {
+        let input: Vec<u8> = vec![3, 1, 97, 1, 1, 98, 2, 2, 97, 97, 3];
+        let expected: HashMap<String, u8> = vec![("a", 1), ("b", 2), ("aa", 3)]
             .into_iter()
-            .map(|s| s.to_string())
+            .map(|(key, value)| (key.to_string(), value))
             .collect();
 
-        assert_eq!(deserialize::<crate::Set<String>>(&input).unwrap(), expected);
+        assert_eq!(
+            deserialize::<HashMap<String, u8>>(&input).unwrap(),
+            expected
+        );
+        assert_eq!(
+            deserialize::<BTreeMap<String, u8>>(&input).unwrap(),
+            expected.into_iter().collect()
+        );
+    }
+
+    #[test]
+    fn deserialize_unordered_map() {
+        let input: Vec<u8> = vec![2, 1, 98, 2, 1, 97, 1];
+        assert_eq!(
+            deserialize::<HashMap<String, u8>>(&input).unwrap_err(),
+            CordError::ValidationError("Unordered map keys")
+        );
+
+        let input: Vec<u8> = vec![2, 1, 97, 1, 1, 97, 2];
+        assert_eq!(
+            deserialize::<HashMap<String, u8>>(&input).unwrap_err(),
+            CordError::ValidationError("Unordered map keys")
+        );
+    }
+
+    #[test]
+    fn deserialize_map_trusting_key_ord() {
+        let input: Vec<u8> = vec![2, 1, 98, 2, 1, 97, 1];
+        let expected: BTreeMap<String, u8> = vec![(String::from("a"), 1), (String::from("b"), 2)]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            deserialize_with_options::<BTreeMap<String, u8>>(
+                &input,
+                Options::new().trust_key_ord()
+            )
+            .unwrap(),
+            expected
+        );
+
+        let input: Vec<u8> = vec![2, 1, 98, 2, 1, 98, 1];
+        assert_eq!(
+            deserialize_with_options::<BTreeMap<String, u8>>(
+                &input,
+                Options::new().trust_key_ord()
+            )
+            .unwrap_err(),
+            CordError::ValidationError("Duplicate map key")
+        );
+    }
+
+    #[test]
+    fn deserialize_order_preserving_map() {
+        let input: Vec<u8> = vec![1, 0, 1, 7, 1, 0, 2, 9, 0];
+        let decoded =
+            deserialize_with_options::<BTreeMap<u16, u8>>(&input, Options::order_preserving())
+                .unwrap();
+        assert_eq!(decoded, vec![(1, 7), (2, 9)].into_iter().collect());
     }
 
     #[test]
@@ -622,6 +2667,284 @@ mod tests {
         );
     }
 
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum Empty {
+        Tuple(),
+        Struct {},
+        Value(u8),
+    }
+
+    #[test]
+    fn deserialize_zero_field_variants() {
+        assert_eq!(deserialize::<Empty>(&[0]).unwrap(), Empty::Tuple());
+        assert_eq!(deserialize::<Empty>(&[1]).unwrap(), Empty::Struct {});
+        assert_eq!(deserialize::<Empty>(&[2, 7]).unwrap(), Empty::Value(7));
+        assert!(deserialize::<Empty>(&[0, 0]).is_err());
+
+        let options = Options::new().struct_encoding(StructEncoding::LengthPrefixed);
+        assert_eq!(
+            deserialize_with_options::<Empty>(&[0], options).unwrap(),
+            Empty::Tuple()
+        );
+        assert_eq!(
+            deserialize_with_options::<Empty>(&[1, 0], options).unwrap(),
+            Empty::Struct {}
+        );
+    }
+
+    #[test]
+    fn deserialize_fixed_variant_index() {
+        let options = Options::new().fixed_variant_index();
+        assert_eq!(
+            deserialize_with_options::<Enum>(&[0, 0, 0, 0], options).unwrap(),
+            Enum::Unit
+        );
+        assert_eq!(
+            deserialize_with_options::<Enum>(&[3, 0, 0, 0, 1], options).unwrap(),
+            Enum::Struct { field: 1 }
+        );
+        assert_eq!(
+            deserialize_with_options::<Enum>(&[3, 0, 0], options).unwrap_err(),
+            CordError::ValidationError("Unexpected end of stream")
+        );
+        assert!(deserialize_with_options::<Enum>(&[0, 0, 0, 1], options).is_err());
+    }
+
+    #[test]
+    fn deserialize_128_bit_integers() {
+        assert_eq!(deserialize::<u128>(&[172, 2]).unwrap(), 300);
+        assert_eq!(deserialize::<i128>(&[215, 4]).unwrap(), -300);
+
+        let mut input = vec![0xff; 18];
+        input.push(0x03);
+        assert_eq!(deserialize::<u128>(&input).unwrap(), u128::MAX);
+        assert_eq!(deserialize::<i128>(&input).unwrap(), i128::MIN);
+
+        input[18] = 0x04;
+        assert_eq!(
+            deserialize::<u128>(&input).unwrap_err(),
+            CordError::ValidationError("Invalid varint")
+        );
+
+        let options = Options::new().int_encoding(IntEncoding::FixedBigEndian);
+        assert_eq!(
+            deserialize_with_options::<i128>(&i128::MAX.to_be_bytes(), options).unwrap(),
+            -1
+        );
+        assert!(deserialize_with_options::<u128>(&[0; 15], options).is_err());
+    }
+
+    #[test]
+    fn deserialize_optional_collections() {
+        assert_eq!(deserialize::<Option<Vec<u8>>>(&[0]).unwrap(), None);
+        assert_eq!(
+            deserialize::<Option<Vec<u8>>>(&[1, 0]).unwrap(),
+            Some(Vec::new())
+        );
+        assert_eq!(
+            deserialize::<Option<Vec<u8>>>(&[1, 1, 0]).unwrap(),
+            Some(vec![0])
+        );
+
+        assert_eq!(deserialize::<Option<String>>(&[0]).unwrap(), None);
+        assert_eq!(
+            deserialize::<Option<String>>(&[1, 0]).unwrap(),
+            Some(String::new())
+        );
+        assert_eq!(
+            deserialize::<Option<String>>(&[1, 1, 97]).unwrap(),
+            Some(String::from("a"))
+        );
+
+        assert_eq!(
+            deserialize::<Option<crate::Set<String>>>(&[0]).unwrap(),
+            None
+        );
+        assert_eq!(
+            deserialize::<Option<crate::Set<String>>>(&[1, 0]).unwrap(),
+            Some(crate::Set::from(Vec::new()))
+        );
+        assert_eq!(
+            deserialize::<Option<crate::Set<String>>>(&[1, 2, 1, 97, 1, 98]).unwrap(),
+            Some(crate::Set::from(vec![String::from("a"), String::from("b")]))
+        );
+    }
+
+    #[test]
+    fn deserialize_nested_option() {
+        assert_eq!(deserialize::<Option<Option<u32>>>(&[0]).unwrap(), None);
+        assert_eq!(
+            deserialize::<Option<Option<u32>>>(&[1, 0]).unwrap(),
+            Some(None)
+        );
+        assert_eq!(
+            deserialize::<Option<Option<u32>>>(&[1, 1, 0]).unwrap(),
+            Some(Some(0))
+        );
+        assert_eq!(
+            deserialize::<Option<Option<u32>>>(&[1, 1, 5]).unwrap(),
+            Some(Some(5))
+        );
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Marker;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Marked {
+        before: u8,
+        marker: Marker,
+        after: u8,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum MarkerEnum {
+        Unit,
+        Marker(Marker),
+        Value(u8),
+    }
+
+    #[test]
+    fn deserialize_unit_structs() {
+        assert_eq!(deserialize::<Marker>(&[]).unwrap(), Marker);
+        assert_eq!(
+            deserialize::<Marked>(&[1, 2]).unwrap(),
+            Marked {
+                before: 1,
+                marker: Marker,
+                after: 2
+            }
+        );
+        assert_eq!(deserialize::<MarkerEnum>(&[0]).unwrap(), MarkerEnum::Unit);
+        assert_eq!(
+            deserialize::<MarkerEnum>(&[1]).unwrap(),
+            MarkerEnum::Marker(Marker)
+        );
+        assert_eq!(
+            deserialize::<MarkerEnum>(&[2, 7]).unwrap(),
+            MarkerEnum::Value(7)
+        );
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Phantom<T> {
+        before: u8,
+        marker: std::marker::PhantomData<T>,
+        after: u16,
+    }
+
+    #[test]
+    fn deserialize_phantom_data() {
+        assert_eq!(
+            deserialize::<Phantom<String>>(&[1, 172, 2]).unwrap(),
+            Phantom {
+                before: 1,
+                marker: std::marker::PhantomData,
+                after: 300
+            }
+        );
+    }
+
+    #[derive(Debug, Deserialize)]
+    enum Never {}
+
+    #[test]
+    fn deserialize_uninhabited_enum() {
+        assert!(matches!(
+            deserialize::<Never>(&[0]).unwrap_err(),
+            CordError::DeserializationError(_)
+        ));
+        assert_eq!(
+            deserialize::<Never>(&[]).unwrap_err(),
+            CordError::ValidationError("Invalid varint")
+        );
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Versioned {
+        first: u8,
+        #[serde(default)]
+        second: u8,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum VersionedVariant {
+        Struct { field: u8 },
+    }
+
+    #[test]
+    fn deserialize_struct_encodings() {
+        let options = Options::new().struct_encoding(StructEncoding::LengthPrefixed);
+        assert_eq!(
+            deserialize_with_options::<Versioned>(&[2, 1, 2], options).unwrap(),
+            Versioned {
+                first: 1,
+                second: 2
+            }
+        );
+        assert_eq!(
+            deserialize_with_options::<Versioned>(&[1, 1], options).unwrap(),
+            Versioned {
+                first: 1,
+                second: 0
+            }
+        );
+        assert_eq!(
+            deserialize_with_options::<Versioned>(&[3, 1, 2, 3], options).unwrap_err(),
+            CordError::ValidationError("Unexpected field count")
+        );
+        assert_eq!(
+            deserialize_with_options::<VersionedVariant>(&[0, 1, 7], options).unwrap(),
+            VersionedVariant::Struct { field: 7 }
+        );
+
+        let options = Options::new().struct_encoding(StructEncoding::StrictLengthPrefixed);
+        assert!(deserialize_with_options::<Versioned>(&[2, 1, 2], options).is_ok());
+        assert_eq!(
+            deserialize_with_options::<Versioned>(&[1, 1], options).unwrap_err(),
+            CordError::ValidationError("Unexpected field count")
+        );
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Tagged {
+        #[allow(dead_code)]
+        id: u8,
+        #[allow(dead_code)]
+        tags: crate::Set<String>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    enum Wrapper {
+        Struct {
+            #[allow(dead_code)]
+            inner: Tagged,
+        },
+    }
+
+    #[test]
+    fn deserialize_error_path() {
+        let input: Vec<u8> = vec![0, 7, 2, 1, 98, 1, 97];
+        let err = deserialize::<Wrapper>(&input).unwrap_err();
+        assert_eq!(err.path(), ["inner", "tags"]);
+        assert_eq!(
+            err.root_cause(),
+            &CordError::DeserializationError(String::from("unordered set"))
+        );
+        assert_eq!(
+            err.to_string(),
+            "Cord deserialization error: unordered set at inner.tags"
+        );
+
+        let input: Vec<u8> = vec![0, 7, 1, 5, 97];
+        let err = deserialize::<Wrapper>(&input).unwrap_err();
+        assert_eq!(err.path(), ["inner", "tags"]);
+        assert_eq!(
+            err.root_cause(),
+            &CordError::ValidationError("Length prefix exceeds input")
+        );
+    }
+
     #[test]
     fn deserialize_struct() {
         let input: Vec<u8> = vec![
@@ -638,4 +2961,49 @@ mod tests {
             }
         );
     }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn deserialize_bytes_crate() {
+        let input: Vec<u8> = vec![3, 0, 1, 2];
+        let decoded = deserialize::<bytes::Bytes>(&input).unwrap();
+        assert_eq!(decoded, bytes::Bytes::from_static(&[0, 1, 2]));
+        assert_eq!(crate::serialize(&decoded).unwrap(), input);
+        assert_eq!(Bytes::from(decoded), Bytes::from(vec![0, 1, 2]));
+
+        let options = Options::order_preserving();
+        let input: Vec<u8> = vec![1, 0, 255, 0, 1];
+        assert_eq!(
+            deserialize_with_options::<bytes::Bytes>(&input, options).unwrap(),
+            bytes::Bytes::from_static(&[1, 0])
+        );
+    }
+
+    #[cfg(feature = "smallvec")]
+    #[test]
+    fn deserialize_smallvec() {
+        use smallvec::SmallVec;
+
+        let inline = deserialize::<SmallVec<[u32; 4]>>(&[2, 1, 2]).unwrap();
+        assert_eq!(inline.as_slice(), [1, 2]);
+        assert!(!inline.spilled());
+
+        let spilled = deserialize::<SmallVec<[u32; 4]>>(&[5, 1, 2, 3, 4, 5]).unwrap();
+        assert_eq!(spilled.as_slice(), [1, 2, 3, 4, 5]);
+        assert!(spilled.spilled());
+
+        assert_eq!(crate::serialize(&spilled).unwrap(), [5, 1, 2, 3, 4, 5]);
+    }
+
+    #[cfg(feature = "arrayvec")]
+    #[test]
+    fn deserialize_arrayvec() {
+        use arrayvec::ArrayVec;
+
+        let decoded = deserialize::<ArrayVec<u32, 4>>(&[2, 1, 2]).unwrap();
+        assert_eq!(decoded.as_slice(), [1, 2]);
+        assert_eq!(crate::serialize(&decoded).unwrap(), [2, 1, 2]);
+
+        assert!(deserialize::<ArrayVec<u32, 4>>(&[5, 1, 2, 3, 4, 5]).is_err());
+    }
 }
@@ -0,0 +1,161 @@
+use integer_encoding::VarInt;
+use std::fmt::Write;
+
+/// Renders `bytes` in the canonical `hexdump -C` layout of offset, hex and ASCII columns.
+pub fn hexdump(bytes: &[u8]) -> String {
+    let mut output = String::new();
+
+    for (line, chunk) in bytes.chunks(16).enumerate() {
+        let _ = write!(output, "{:08x}  ", line * 16);
+        for index in 0..16 {
+            match chunk.get(index) {
+                Some(byte) => {
+                    let _ = write!(output, "{byte:02x} ");
+                }
+                None => output.push_str("   "),
+            }
+            if index == 7 {
+                output.push(' ');
+            }
+        }
+
+        output.push_str(" |");
+        output.extend(chunk.iter().map(|byte| match byte {
+            0x20..=0x7e => *byte as char,
+            _ => '.',
+        }));
+        output.push_str("|\n");
+    }
+
+    let _ = writeln!(output, "{:08x}", bytes.len());
+    output
+}
+
+/// Lists plausible interpretations of the leading bytes of an encoding whose type is unknown,
+/// one per line. The format is not self-describing, so this is a guess rather than a parse.
+pub fn describe_prefix(bytes: &[u8]) -> String {
+    let mut output = String::new();
+    let first = match bytes.first() {
+        Some(first) => *first,
+        None => return String::from("empty input\n"),
+    };
+
+    match first {
+        0 => output.push_str("bool false, option tag absent, or unit variant 0\n"),
+        1 => output.push_str("bool true, or option tag present\n"),
+        _ => {}
+    }
+
+    if let Some((value, size)) = u64::decode_var(bytes) {
+        let plural = if size == 1 { "" } else { "s" };
+        let _ = writeln!(output, "varint = {value} ({size} byte{plural})");
+        let _ = writeln!(
+            output,
+            "zigzag varint = {}",
+            i64::decode_var(bytes).unwrap().0
+        );
+
+        let rest = &bytes[size..];
+        match usize::try_from(value) {
+            Ok(len) if len <= rest.len() => {
+                let _ = write!(output, "length-prefixed bytes of len {len}");
+                match std::str::from_utf8(&rest[..len]) {
+                    Ok(string) => {
+                        let _ = writeln!(output, ": {string:?}");
+                    }
+                    Err(_) => output.push('\n'),
+                }
+            }
+            _ => {
+                let _ = writeln!(
+                    output,
+                    "length prefix {value} exceeds the remaining {} bytes",
+                    rest.len()
+                );
+            }
+        }
+    } else {
+        output.push_str("no valid varint\n");
+    }
+
+    if let Some(prefix) = bytes.get(..4) {
+        let value = u32::from_be_bytes(prefix.try_into().unwrap());
+        let _ = writeln!(output, "fixed-width u32 = {value}");
+    }
+    if let Some(prefix) = bytes.get(..8) {
+        let value = u64::from_be_bytes(prefix.try_into().unwrap());
+        let _ = writeln!(output, "fixed-width u64 = {value}");
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{describe_prefix, hexdump};
+
+    #[test]
+    fn describe_length_prefixed_string() {
+        assert_eq!(
+            describe_prefix(&[4, 116, 101, 115, 116]),
+            concat!(
+                "varint = 4 (1 byte)\n",
+                "zigzag varint = 2\n",
+                "length-prefixed bytes of len 4: \"test\"\n",
+                "fixed-width u32 = 74737011\n"
+            )
+        );
+    }
+
+    #[test]
+    fn describe_varint() {
+        assert_eq!(
+            describe_prefix(&[172, 2]),
+            concat!(
+                "varint = 300 (2 bytes)\n",
+                "zigzag varint = 150\n",
+                "length prefix 300 exceeds the remaining 0 bytes\n"
+            )
+        );
+    }
+
+    #[test]
+    fn describe_tags() {
+        assert_eq!(
+            describe_prefix(&[1, 255]),
+            concat!(
+                "bool true, or option tag present\n",
+                "varint = 1 (1 byte)\n",
+                "zigzag varint = -1\n",
+                "length-prefixed bytes of len 1\n"
+            )
+        );
+        assert_eq!(describe_prefix(&[]), "empty input\n");
+        assert_eq!(describe_prefix(&[255]), "no valid varint\n");
+    }
+
+    #[test]
+    fn hexdump_short_input() {
+        assert_eq!(
+            hexdump(&[4, 116, 101, 115, 116]),
+            "00000000  04 74 65 73 74                                    |.test|\n00000005\n"
+        );
+    }
+
+    #[test]
+    fn hexdump_multiple_lines() {
+        let input: Vec<u8> = (0x41..0x41 + 18).collect();
+        assert_eq!(
+            hexdump(&input),
+            concat!(
+                "00000000  41 42 43 44 45 46 47 48  49 4a 4b 4c 4d 4e 4f 50  |ABCDEFGHIJKLMNOP|\n",
+                "00000010  51 52                                             |QR|\n",
+                "00000012\n"
+            )
+        );
+    }
+
+    #[test]
+    fn hexdump_empty_input() {
+        assert_eq!(hexdump(&[]), "00000000\n");
+    }
+}
@@ -0,0 +1,109 @@
+//! Encodes a C-like enum by its discriminant rather than its position, for use with
+//! `#[serde(with = "cord::discriminant")]`.
+//!
+//! serde's derive writes the index of a variant within its declaration, so that for
+//! `enum Code { A = 10, B = 20 }`, `Code::B` is written as `1`. This module writes `20`
+//! instead, as a `u32` under [`int_encoding`](crate::Options::int_encoding): a varint by
+//! default, or four big-endian bytes under
+//! [`FixedBigEndian`](crate::IntEncoding::FixedBigEndian). Variant indices use the same
+//! encoding unless [`fixed_variant_index`](crate::Options::fixed_variant_index) is set, which
+//! writes them as a little-endian `u32` but leaves discriminants as they are. The enum provides
+//! the mapping by implementing `From<Code> for u32` and `TryFrom<u32> for Code`. Given those,
+//! the container attribute `#[serde(into = "u32", try_from = "u32")]` produces the same
+//! encoding wherever the enum is used.
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::convert::TryFrom;
+
+pub fn serialize<S, E>(value: &E, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    E: Copy + Into<u32>,
+{
+    (*value).into().serialize(serializer)
+}
+
+pub fn deserialize<'de, D, E>(deserializer: D) -> Result<E, D::Error>
+where
+    D: Deserializer<'de>,
+    E: TryFrom<u32>,
+{
+    let discriminant = u32::deserialize(deserializer)?;
+    E::try_from(discriminant)
+        .map_err(|_| de::Error::custom(format!("unknown discriminant {discriminant}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{deserialize, serialize, serialize_with_options, CordError, IntEncoding, Options};
+    use serde::{Deserialize, Serialize};
+    use std::convert::TryFrom;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(into = "u32", try_from = "u32")]
+    enum Code {
+        A = 10,
+        B = 300,
+    }
+
+    impl From<Code> for u32 {
+        fn from(code: Code) -> u32 {
+            code as u32
+        }
+    }
+
+    impl TryFrom<u32> for Code {
+        type Error = String;
+
+        fn try_from(value: u32) -> Result<Self, String> {
+            match value {
+                10 => Ok(Code::A),
+                300 => Ok(Code::B),
+                _ => Err(format!("unknown code {value}")),
+            }
+        }
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Message {
+        #[serde(with = "crate::discriminant")]
+        code: Code,
+    }
+
+    #[test]
+    fn discriminant_field() {
+        let message = Message { code: Code::B };
+        let bytes = serialize(&message).unwrap();
+        assert_eq!(bytes, [172, 2]);
+        assert_eq!(deserialize::<Message>(&bytes).unwrap(), message);
+
+        let err = deserialize::<Message>(&[1]).unwrap_err();
+        assert_eq!(err.path(), ["code"]);
+        assert_eq!(
+            err.root_cause(),
+            &CordError::DeserializationError(String::from("unknown discriminant 1"))
+        );
+    }
+
+    #[test]
+    fn discriminant_options() {
+        let message = Message { code: Code::B };
+        let options = Options::new().fixed_variant_index();
+        assert_eq!(serialize_with_options(&message, options).unwrap(), [172, 2]);
+        let options = Options::new().int_encoding(IntEncoding::FixedBigEndian);
+        assert_eq!(
+            serialize_with_options(&message, options).unwrap(),
+            [0, 0, 1, 44]
+        );
+    }
+
+    #[test]
+    fn discriminant_container() {
+        assert_eq!(serialize(&Code::A).unwrap(), [10]);
+        assert_eq!(deserialize::<Code>(&[10]).unwrap(), Code::A);
+        assert_eq!(
+            deserialize::<Code>(&[11]).unwrap_err(),
+            CordError::DeserializationError(String::from("unknown code 11"))
+        );
+    }
+}
@@ -0,0 +1,192 @@
+//! Writes a nested struct's fields in place within its parent, for use with
+//! `#[serde(with = "cord::embed")]`.
+//!
+//! Cord has no support for `#[serde(flatten)]`, which routes fields through a map. Since fields
+//! are positional, composing structs needs no map: under the default
+//! [`StructEncoding::Positional`](crate::StructEncoding::Positional) a nested struct is already
+//! written as its bare fields. Under the length-prefixed struct encodings, an embedded struct
+//! omits its own field count, so its fields read as a continuation of the parent's and the
+//! parent counts the embedded struct as a single field.
+//!
+//! Only a struct, possibly within an `Option`, omits its field count. Other values, such as a
+//! `Vec` of structs, are written as they would be without the attribute. An embedded struct's
+//! fields become part of the parent's layout: adding, removing or reordering them changes the
+//! encoding of every parent exactly as if the parent's own fields had changed, and fields added
+//! to the embedded struct are not picked up as trailing defaults.
+//!
+//! Other formats see a newtype struct around the value.
+
+use serde::de::{Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+use std::fmt::Formatter;
+use std::marker::PhantomData;
+
+// Reserved name through which an embedded struct asks Cord's (de)serializer to omit its field count
+pub(crate) const EMBED: &str = "$cord::Embed";
+
+pub fn serialize<S, T>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: ?Sized + Serialize,
+{
+    serializer.serialize_newtype_struct(EMBED, value)
+}
+
+pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    deserializer.deserialize_newtype_struct(
+        EMBED,
+        EmbedVisitor {
+            marker: PhantomData,
+        },
+    )
+}
+
+struct EmbedVisitor<T> {
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<'de, T> Visitor<'de> for EmbedVisitor<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("an embedded struct")
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        deserialize, deserialize_with_options, serialize, serialize_with_options, Options,
+        StructEncoding,
+    };
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Network {
+        host: String,
+        port: u16,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Config {
+        name: String,
+        #[serde(with = "crate::embed")]
+        network: Network,
+        retries: u8,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Nested {
+        name: String,
+        network: Network,
+        retries: u8,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Flat {
+        name: String,
+        host: String,
+        port: u16,
+        retries: u8,
+    }
+
+    fn config() -> Config {
+        Config {
+            name: String::from("a"),
+            network: Network {
+                host: String::from("h"),
+                port: 80,
+            },
+            retries: 3,
+        }
+    }
+
+    #[test]
+    fn embed_positional() {
+        let flat = Flat {
+            name: String::from("a"),
+            host: String::from("h"),
+            port: 80,
+            retries: 3,
+        };
+        let bytes = serialize(&config()).unwrap();
+        assert_eq!(bytes, [1, 97, 1, 104, 80, 3]);
+        assert_eq!(bytes, serialize(&flat).unwrap());
+        assert_eq!(deserialize::<Config>(&bytes).unwrap(), config());
+    }
+
+    #[test]
+    fn embed_length_prefixed() {
+        let options = Options::new().struct_encoding(StructEncoding::LengthPrefixed);
+        let nested = Nested {
+            name: String::from("a"),
+            network: Network {
+                host: String::from("h"),
+                port: 80,
+            },
+            retries: 3,
+        };
+        assert_eq!(
+            serialize_with_options(&nested, options).unwrap(),
+            [3, 1, 97, 2, 1, 104, 80, 3]
+        );
+
+        let bytes = serialize_with_options(&config(), options).unwrap();
+        assert_eq!(bytes, [3, 1, 97, 1, 104, 80, 3]);
+        assert_eq!(
+            deserialize_with_options::<Config>(&bytes, options).unwrap(),
+            config()
+        );
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Net {
+        first: u8,
+        second: u8,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Routes {
+        #[serde(with = "crate::embed")]
+        nets: Vec<Net>,
+        metric: u8,
+    }
+
+    #[test]
+    fn embed_seq_length_prefixed() {
+        let options = Options::new().struct_encoding(StructEncoding::LengthPrefixed);
+        let routes = Routes {
+            nets: vec![
+                Net {
+                    first: 1,
+                    second: 2,
+                },
+                Net {
+                    first: 3,
+                    second: 4,
+                },
+            ],
+            metric: 9,
+        };
+        let bytes = serialize_with_options(&routes, options).unwrap();
+        assert_eq!(bytes, [2, 2, 2, 1, 2, 2, 3, 4, 9]);
+        assert_eq!(
+            deserialize_with_options::<Routes>(&bytes, options).unwrap(),
+            routes
+        );
+    }
+}
@@ -0,0 +1,71 @@
+use std::convert::TryInto;
+
+pub(crate) trait FixedInt: Sized {
+    const SIZE: usize;
+    type Encoded: AsRef<[u8]>;
+
+    fn encode_fixed(self) -> Self::Encoded;
+    fn decode_fixed(bytes: &[u8]) -> Option<Self>;
+
+    // Little-endian two's complement, the layout of packed integer vectors
+    fn encode_le(self) -> Self::Encoded;
+    fn decode_le(bytes: &[u8]) -> Option<Self>;
+}
+
+macro_rules! impl_fixed_unsigned {
+    ($($int:ty),*) => {
+        $(
+            impl FixedInt for $int {
+                const SIZE: usize = std::mem::size_of::<$int>();
+                type Encoded = [u8; std::mem::size_of::<$int>()];
+
+                fn encode_fixed(self) -> Self::Encoded {
+                    self.to_be_bytes()
+                }
+
+                fn decode_fixed(bytes: &[u8]) -> Option<Self> {
+                    Some(<$int>::from_be_bytes(bytes.try_into().ok()?))
+                }
+
+                fn encode_le(self) -> Self::Encoded {
+                    self.to_le_bytes()
+                }
+
+                fn decode_le(bytes: &[u8]) -> Option<Self> {
+                    Some(<$int>::from_le_bytes(bytes.try_into().ok()?))
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_fixed_signed {
+    ($(($int:ty, $unsigned:ty)),*) => {
+        $(
+            impl FixedInt for $int {
+                const SIZE: usize = std::mem::size_of::<$int>();
+                type Encoded = [u8; std::mem::size_of::<$int>()];
+
+                fn encode_fixed(self) -> Self::Encoded {
+                    ((self as $unsigned) ^ (1 << (<$unsigned>::BITS - 1))).encode_fixed()
+                }
+
+                fn decode_fixed(bytes: &[u8]) -> Option<Self> {
+                    let flipped = <$unsigned>::decode_fixed(bytes)?;
+                    Some((flipped ^ (1 << (<$unsigned>::BITS - 1))) as $int)
+                }
+
+                fn encode_le(self) -> Self::Encoded {
+                    self.to_le_bytes()
+                }
+
+                fn decode_le(bytes: &[u8]) -> Option<Self> {
+                    Some(<$int>::from_le_bytes(bytes.try_into().ok()?))
+                }
+            }
+        )*
+    };
+}
+
+impl_fixed_unsigned!(u8, u16, u32, u64, u128);
+impl_fixed_signed!((i8, u8), (i16, u16), (i32, u32), (i64, u64), (i128, u128));
@@ -0,0 +1,255 @@
+use crate::de::checked_len_add;
+use crate::{deserialize, serialize, CordError, CordResult};
+use integer_encoding::VarInt;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io::{BufRead, Read, Write};
+
+// A varint-encoded `u64` never occupies more than ten bytes
+pub(crate) const MAX_LENGTH_PREFIX: usize = 10;
+
+pub fn write_frame<W, T>(writer: &mut W, value: &T) -> CordResult<()>
+where
+    W: ?Sized + Write,
+    T: ?Sized + Serialize,
+{
+    let body = serialize(value)?;
+    writer.write_all(&body.len().encode_var_vec())?;
+    writer.write_all(&body)?;
+    Ok(())
+}
+
+pub fn read_frame<R, T>(reader: &mut R) -> CordResult<T>
+where
+    R: ?Sized + Read,
+    T: DeserializeOwned,
+{
+    let mut prefix = Vec::with_capacity(MAX_LENGTH_PREFIX);
+    loop {
+        let mut byte = [0_u8; 1];
+        reader.read_exact(&mut byte)?;
+        prefix.push(byte[0]);
+
+        if let Some((len, _)) = usize::decode_var(&prefix) {
+            let mut body = Vec::new();
+            let limit =
+                u64::try_from(len).map_err(|_| CordError::ValidationError("length overflow"))?;
+            reader.take(limit).read_to_end(&mut body)?;
+            if body.len() != len {
+                return Err(CordError::ValidationError("Incomplete frame"));
+            }
+            return deserialize(&body);
+        }
+
+        if prefix.len() >= MAX_LENGTH_PREFIX {
+            return Err(CordError::ValidationError("Invalid varint"));
+        }
+    }
+}
+
+/// The largest frame body a [`FrameReader`] accepts unless told otherwise.
+pub const DEFAULT_MAX_FRAME_LEN: usize = 16 << 20;
+
+/// Reads successive frames from a source that may still be growing, such as a log file
+/// being appended to.
+///
+/// [`FrameReader::next`] returns `Ok(None)` once the source is exhausted on a frame
+/// boundary. When it is exhausted mid-frame, it returns `ValidationError("Incomplete frame")`
+/// but keeps the partial frame buffered, so that a later call resumes once more data arrives.
+///
+/// A frame that fails to decode, or whose length exceeds the reader's maximum, is skipped
+/// whole before the error is returned, so that the next call starts on the following frame.
+/// Frames too long to buffer are discarded as they are read.
+pub struct FrameReader<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    max_frame_len: usize,
+    // Bytes of a rejected frame still to be discarded
+    skipping: usize,
+}
+
+impl<R: BufRead> FrameReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: Vec::new(),
+            max_frame_len: DEFAULT_MAX_FRAME_LEN,
+            skipping: 0,
+        }
+    }
+
+    /// Rejects frames whose body is longer than `max_frame_len` bytes. Defaults to
+    /// [`DEFAULT_MAX_FRAME_LEN`].
+    pub fn max_frame_len(mut self, max_frame_len: usize) -> Self {
+        self.max_frame_len = max_frame_len;
+        self
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn next<T: DeserializeOwned>(&mut self) -> CordResult<Option<T>> {
+        loop {
+            if let Some(value) = self.take_buffered()? {
+                return Ok(Some(value));
+            }
+
+            let available = self.reader.fill_buf()?;
+            if available.is_empty() {
+                return if self.buffer.is_empty() && self.skipping == 0 {
+                    Ok(None)
+                } else {
+                    Err(CordError::ValidationError("Incomplete frame"))
+                };
+            }
+
+            let read = available.len();
+            self.buffer.extend_from_slice(available);
+            self.reader.consume(read);
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn take_buffered<T: DeserializeOwned>(&mut self) -> CordResult<Option<T>> {
+        let skipped = self.skipping.min(self.buffer.len());
+        self.buffer.drain(..skipped);
+        self.skipping -= skipped;
+        if self.skipping > 0 {
+            return Ok(None);
+        }
+
+        let (len, prefix) = match usize::decode_var(&self.buffer) {
+            Some(decoded) => decoded,
+            None if self.buffer.len() >= MAX_LENGTH_PREFIX => {
+                return Err(CordError::ValidationError("Invalid varint"))
+            }
+            None => return Ok(None),
+        };
+
+        let end = checked_len_add(prefix, len)?;
+        if len > self.max_frame_len {
+            self.buffer.drain(..prefix);
+            self.skipping = len;
+            return Err(CordError::ValidationError("Frame length exceeds limit"));
+        }
+        if self.buffer.len() < end {
+            return Ok(None);
+        }
+
+        let value = deserialize(&self.buffer[prefix..end]);
+        self.buffer.drain(..end);
+        value.map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_frame, write_frame, FrameReader};
+    use crate::CordError;
+    use std::cell::RefCell;
+    use std::io::{BufReader, Cursor, Read};
+    use std::rc::Rc;
+
+    #[test]
+    fn write_and_read_frames() {
+        let mut output = Vec::new();
+        write_frame(&mut output, "test").unwrap();
+        write_frame(&mut output, &300_u16).unwrap();
+        assert_eq!(output, [5, 4, 116, 101, 115, 116, 2, 172, 2]);
+
+        let mut input = Cursor::new(output);
+        assert_eq!(read_frame::<_, String>(&mut input).unwrap(), "test");
+        assert_eq!(read_frame::<_, u16>(&mut input).unwrap(), 300);
+        assert!(read_frame::<_, u16>(&mut input).is_err());
+    }
+
+    #[test]
+    fn read_truncated_frame() {
+        let mut input = Cursor::new(vec![5, 4, 116]);
+        assert_eq!(
+            read_frame::<_, String>(&mut input).unwrap_err(),
+            CordError::ValidationError("Incomplete frame")
+        );
+    }
+
+    #[test]
+    fn frame_reader_rejects_overflowing_length() {
+        let input: Vec<u8> = vec![255, 255, 255, 255, 255, 255, 255, 255, 255, 1];
+        let mut reader = FrameReader::new(Cursor::new(input));
+        assert_eq!(
+            reader.next::<u8>().unwrap_err(),
+            CordError::ValidationError("length overflow")
+        );
+    }
+
+    #[test]
+    fn frame_reader_rejects_oversized_frame() {
+        let mut input: Vec<u8> = vec![6, 5, 116, 111, 111, 32, 108];
+        input.extend_from_slice(&[2, 172, 2]);
+        let mut reader =
+            FrameReader::new(BufReader::with_capacity(2, Cursor::new(input))).max_frame_len(5);
+
+        assert_eq!(
+            reader.next::<String>().unwrap_err(),
+            CordError::ValidationError("Frame length exceeds limit")
+        );
+        assert_eq!(reader.next::<u16>().unwrap(), Some(300));
+        assert_eq!(reader.next::<u16>().unwrap(), None);
+    }
+
+    #[test]
+    fn frame_reader_skips_undecodable_frame() {
+        let input: Vec<u8> = vec![3, 2, 1, 2, 2, 172, 2];
+        let mut reader = FrameReader::new(Cursor::new(input));
+
+        assert_eq!(
+            reader.next::<u8>().unwrap_err(),
+            CordError::ValidationError("Unexpected trailing bytes")
+        );
+        assert_eq!(reader.next::<u16>().unwrap(), Some(300));
+        assert_eq!(reader.next::<u16>().unwrap(), None);
+    }
+
+    #[test]
+    fn frame_reader_stops_at_clean_eof() {
+        let input: Vec<u8> = vec![5, 4, 116, 101, 115, 116, 2, 172, 2];
+        let mut reader = FrameReader::new(BufReader::with_capacity(2, Cursor::new(input)));
+
+        assert_eq!(reader.next::<String>().unwrap().unwrap(), "test");
+        assert_eq!(reader.next::<u16>().unwrap(), Some(300));
+        assert_eq!(reader.next::<u16>().unwrap(), None);
+    }
+
+    struct Growing {
+        data: Rc<RefCell<Vec<u8>>>,
+        position: usize,
+    }
+
+    impl Read for Growing {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let data = self.data.borrow();
+            let read = (&data[self.position..]).read(buf)?;
+            self.position += read;
+            Ok(read)
+        }
+    }
+
+    #[test]
+    fn frame_reader_resumes_partial_frame() {
+        let data = Rc::new(RefCell::new(vec![5, 4, 116]));
+        let mut reader = FrameReader::new(BufReader::new(Growing {
+            data: data.clone(),
+            position: 0,
+        }));
+
+        assert_eq!(
+            reader.next::<String>().unwrap_err(),
+            CordError::ValidationError("Incomplete frame")
+        );
+
+        data.borrow_mut().extend_from_slice(&[101, 115, 116]);
+        assert_eq!(reader.next::<String>().unwrap().unwrap(), "test");
+        assert_eq!(reader.next::<String>().unwrap(), None);
+    }
+}
@@ -0,0 +1,129 @@
+//! Encodes an `IndexSet` like a [`Set`](crate::Set), for use with
+//! `#[serde(with = "cord::index_set")]`.
+//!
+//! Elements are written in canonical order whatever the order of insertion, and decoding
+//! inserts them in that order, so a decoded set iterates in canonical order. `IndexMap` needs
+//! no such module, since its own implementations already go through Cord's canonically
+//! ordered maps.
+
+use crate::de::{deserialize_set, SetElements};
+use crate::ser::sort_by_encoding;
+use crate::types::SET;
+use indexmap::IndexSet;
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+use std::hash::Hash;
+
+pub fn serialize<S, T>(set: &IndexSet<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Serialize,
+{
+    serializer.serialize_newtype_struct(SET, &sort_by_encoding(set.iter())?)
+}
+
+pub fn deserialize<'de, D, T>(deserializer: D) -> Result<IndexSet<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Hash + Eq + Serialize + Deserialize<'de>,
+{
+    deserialize_set(deserializer)
+}
+
+impl<T: Hash + Eq> SetElements<T> for IndexSet<T> {
+    fn reserve(&mut self, additional: usize) {
+        IndexSet::reserve(self, additional);
+    }
+
+    fn insert_element(&mut self, element: T) -> bool {
+        self.insert(element)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{deserialize, serialize, CordError, Set};
+    use indexmap::{IndexMap, IndexSet};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Tags {
+        #[serde(with = "crate::index_set")]
+        tags: IndexSet<String>,
+    }
+
+    #[test]
+    fn index_set_in_canonical_order() {
+        let tags: IndexSet<String> = ["aa", "z", "b"].iter().map(|s| s.to_string()).collect();
+        let bytes = serialize(&Tags { tags }).unwrap();
+        assert_eq!(bytes, [3, 1, 98, 1, 122, 2, 97, 97]);
+
+        let set: Set<&str> = ["aa", "z", "b"].iter().copied().collect();
+        assert_eq!(bytes, serialize(&set).unwrap());
+
+        let decoded = deserialize::<Tags>(&bytes).unwrap();
+        assert_eq!(decoded.tags.iter().collect::<Vec<_>>(), ["b", "z", "aa"]);
+
+        let err = deserialize::<Tags>(&[2, 2, 97, 97, 1, 98]).unwrap_err();
+        assert_eq!(
+            err.root_cause(),
+            &CordError::DeserializationError(String::from("unordered set"))
+        );
+    }
+
+    #[test]
+    fn index_set_with_duplicate() {
+        let err = deserialize::<Tags>(&[2, 1, 98, 1, 98]).unwrap_err();
+        assert_eq!(
+            err.root_cause(),
+            &CordError::DeserializationError(String::from("unordered set"))
+        );
+    }
+
+    // Encodes any letter's case as written, yet compares letters regardless of case
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Letter(String);
+
+    impl PartialEq for Letter {
+        fn eq(&self, other: &Self) -> bool {
+            self.0.eq_ignore_ascii_case(&other.0)
+        }
+    }
+
+    impl Eq for Letter {}
+
+    impl std::hash::Hash for Letter {
+        fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+            self.0.to_ascii_lowercase().hash(state);
+        }
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Letters {
+        #[serde(with = "crate::index_set")]
+        #[allow(dead_code)]
+        letters: IndexSet<Letter>,
+    }
+
+    #[test]
+    fn index_set_with_equal_elements() {
+        let err = deserialize::<Letters>(&[2, 1, 65, 1, 97]).unwrap_err();
+        assert_eq!(
+            err.root_cause(),
+            &CordError::DeserializationError(String::from("duplicate set element"))
+        );
+    }
+
+    #[test]
+    fn index_map_in_canonical_order() {
+        let mut map = IndexMap::new();
+        map.insert(String::from("aa"), 1_u8);
+        map.insert(String::from("b"), 2);
+        let bytes = serialize(&map).unwrap();
+        assert_eq!(bytes, [2, 1, 98, 2, 2, 97, 97, 1]);
+
+        let decoded = deserialize::<IndexMap<String, u8>>(&bytes).unwrap();
+        assert_eq!(decoded.keys().collect::<Vec<_>>(), ["b", "aa"]);
+        assert!(deserialize::<IndexMap<String, u8>>(&[2, 2, 97, 97, 1, 1, 98, 2]).is_err());
+    }
+}
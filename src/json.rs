@@ -0,0 +1,88 @@
+use crate::{deserialize, serialize, CordError, CordResult};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Decodes `bytes` as a `T` and converts it to JSON.
+///
+/// Cord is not self-describing, so the schema is supplied by `T` rather than read from the input.
+pub fn to_json_value<T>(bytes: &[u8]) -> CordResult<serde_json::Value>
+where
+    T: DeserializeOwned + Serialize,
+{
+    let value: T = deserialize(bytes)?;
+    serde_json::to_value(value).map_err(|err| CordError::SerializationError(err.to_string()))
+}
+
+/// Converts JSON to a `T` and encodes it, rejecting JSON that does not match the schema of `T`.
+pub fn from_json_value<T>(value: &serde_json::Value) -> CordResult<Vec<u8>>
+where
+    T: DeserializeOwned + Serialize,
+{
+    let value =
+        T::deserialize(value).map_err(|err| CordError::DeserializationError(err.to_string()))?;
+    serialize(&value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_json_value, to_json_value};
+    use crate::CordError;
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    #[derive(Serialize, Deserialize)]
+    struct Event {
+        id: u32,
+        name: String,
+        tags: Vec<String>,
+        parent: Option<u32>,
+    }
+
+    #[test]
+    fn event_to_json() {
+        let input: Vec<u8> = vec![7, 4, 116, 101, 115, 116, 1, 1, 97, 0];
+        assert_eq!(
+            to_json_value::<Event>(&input).unwrap(),
+            json!({"id": 7, "name": "test", "tags": ["a"], "parent": null})
+        );
+    }
+
+    #[test]
+    fn invalid_input_to_json() {
+        assert_eq!(
+            to_json_value::<Event>(&[7, 4, 116])
+                .unwrap_err()
+                .root_cause(),
+            &CordError::ValidationError("Length prefix exceeds input")
+        );
+    }
+
+    #[test]
+    fn event_from_json() {
+        let value = json!({"id": 7, "name": "test", "tags": ["a"], "parent": null});
+        let bytes = from_json_value::<Event>(&value).unwrap();
+        assert_eq!(bytes, [7, 4, 116, 101, 115, 116, 1, 1, 97, 0]);
+        assert_eq!(to_json_value::<Event>(&bytes).unwrap(), value);
+    }
+
+    #[test]
+    fn raw_bytes_through_json() {
+        let value = to_json_value::<crate::RawBytes<4>>(b"CORD").unwrap();
+        assert_eq!(value, json!([67, 79, 82, 68]));
+        assert_eq!(
+            from_json_value::<crate::RawBytes<4>>(&value).unwrap(),
+            b"CORD"
+        );
+        assert!(from_json_value::<crate::RawBytes<4>>(&json!([67, 79, 82])).is_err());
+    }
+
+    #[test]
+    fn nonconforming_json() {
+        let value = json!({"id": -1, "name": "test", "tags": [], "parent": null});
+        assert!(matches!(
+            from_json_value::<Event>(&value).unwrap_err(),
+            CordError::DeserializationError(_)
+        ));
+        assert!(from_json_value::<Event>(&json!({"id": 7})).is_err());
+    }
+}
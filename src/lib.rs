@@ -1,9 +1,65 @@
+pub mod array;
 mod de;
+#[cfg(feature = "debug")]
+mod debug;
+pub mod discriminant;
+pub mod embed;
+mod fixint;
+mod frame;
+#[cfg(feature = "indexmap")]
+pub mod index_set;
+#[cfg(feature = "json")]
+mod json;
+mod max_len;
+mod options;
+pub mod packed;
+mod pairs;
+#[cfg(any(feature = "crc32", feature = "xxhash"))]
+mod record;
+mod registry;
 mod result;
+mod scaled;
 mod ser;
+#[cfg(feature = "test-util")]
+mod snapshot;
+pub mod system_time;
+mod trace;
 mod types;
+pub mod variant_index;
+mod wide;
+mod writer;
 
-pub use de::deserialize;
+pub use de::{
+    check, deserialize, deserialize_counted, deserialize_owned, deserialize_prefix,
+    deserialize_tolerant, deserialize_versioned, deserialize_with_options, round_trip, Decoder,
+};
+#[cfg(feature = "debug")]
+pub use debug::{describe_prefix, hexdump};
+pub use frame::{read_frame, write_frame, FrameReader, DEFAULT_MAX_FRAME_LEN};
+#[cfg(feature = "json")]
+pub use json::{from_json_value, to_json_value};
+pub use max_len::MaxLen;
+pub use options::{
+    IntEncoding, Options, SeqEncoding, StrEncoding, StructEncoding,
+    DEFAULT_MAX_DICTIONARY_EXPANSION,
+};
+pub use pairs::{deserialize_map_from_pairs, serialize_map_as_pairs};
+#[cfg(any(feature = "crc32", feature = "xxhash"))]
+pub use record::{read_record, write_record, Checksum};
+pub use registry::Registry;
 pub use result::{CordError, CordResult};
-pub use ser::serialize;
-pub use types::{Bytes, DateTime, Set};
+pub use scaled::Scaled;
+#[cfg(feature = "smallvec")]
+pub use ser::serialize_small;
+pub use ser::{
+    hash_into, length_prefix_len, serialize, serialize_into, serialize_into_counted,
+    serialize_into_slice, serialize_prefix, serialize_slice, serialize_versioned,
+    serialize_with_options, signed_varint_len, varint_len, Encoder, FORMAT_VERSION,
+};
+#[cfg(feature = "test-util")]
+pub use snapshot::assert_snapshot;
+pub use types::{
+    BorrowedBytes, Bytes, Date, DateTime, DateTimeWithOffset, DeltaSeq, Micros, Millis,
+    NaiveDateTime, Nanos, Precision, PresortedSet, RawBytes, Seconds, Set, SortedSet, Time,
+    Timestamp,
+};
@@ -0,0 +1,125 @@
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{self, Serialize, Serializer};
+use std::fmt::Formatter;
+use std::marker::PhantomData;
+
+/// Caps a `Vec` field at `N` elements, for use with `#[serde(with = "cord::MaxLen::<16>")]`.
+///
+/// The encoding is that of the `Vec` itself. Decoding rejects a length prefix above `N` before
+/// reading any element, independently of `Options::max_collection_len`, and encoding rejects
+/// a `Vec` that would not decode.
+pub struct MaxLen<const N: usize>;
+
+impl<const N: usize> MaxLen<N> {
+    pub fn serialize<S, T>(value: &[T], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Serialize,
+    {
+        if value.len() > N {
+            return Err(ser::Error::custom(format!(
+                "sequence of {} elements exceeds maximum of {N}",
+                value.len()
+            )));
+        }
+        value.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        deserializer.deserialize_seq(MaxLenVisitor::<T, N> {
+            marker: PhantomData,
+        })
+    }
+}
+
+struct MaxLenVisitor<T, const N: usize> {
+    marker: PhantomData<fn() -> Vec<T>>,
+}
+
+impl<'de, T, const N: usize> Visitor<'de> for MaxLenVisitor<T, N>
+where
+    T: Deserialize<'de>,
+{
+    type Value = Vec<T>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        write!(formatter, "a sequence of at most {N} elements")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        // Cord never hints at more elements than the length prefix declares
+        let hint = seq.size_hint().unwrap_or(0);
+        if hint > N {
+            return Err(de::Error::invalid_length(hint, &self));
+        }
+        let mut elements = Vec::with_capacity(hint);
+        while let Some(element) = seq.next_element()? {
+            if elements.len() == N {
+                return Err(de::Error::invalid_length(N + 1, &self));
+            }
+            elements.push(element);
+        }
+        Ok(elements)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        deserialize, deserialize_with_options, serialize, CordError, Options, SeqEncoding,
+    };
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Message {
+        #[serde(with = "crate::MaxLen::<2>")]
+        tags: Vec<u8>,
+    }
+
+    #[test]
+    fn max_len_roundtrip() {
+        let message = Message { tags: vec![1, 2] };
+        let bytes = serialize(&message).unwrap();
+        assert_eq!(bytes, [2, 1, 2]);
+        assert_eq!(deserialize::<Message>(&bytes).unwrap(), message);
+    }
+
+    #[test]
+    fn max_len_rejects_long_sequences() {
+        assert_eq!(
+            serialize(&Message {
+                tags: vec![1, 2, 3]
+            })
+            .unwrap_err()
+            .root_cause(),
+            &CordError::SerializationError(String::from(
+                "sequence of 3 elements exceeds maximum of 2"
+            ))
+        );
+        assert_eq!(
+            deserialize::<Message>(&[3, 1, 2, 3])
+                .unwrap_err()
+                .root_cause(),
+            &CordError::DeserializationError(String::from(
+                "invalid length 3, expected a sequence of at most 2 elements"
+            ))
+        );
+    }
+
+    #[test]
+    fn max_len_terminated() {
+        let options = Options::new().seq_encoding(SeqEncoding::Terminated);
+        assert_eq!(
+            deserialize_with_options::<Message>(&[1, 1, 1, 2, 0], options).unwrap(),
+            Message { tags: vec![1, 2] }
+        );
+        assert!(deserialize_with_options::<Message>(&[1, 1, 1, 2, 1, 3, 0], options).is_err());
+    }
+}
@@ -0,0 +1,240 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntEncoding {
+    Varint,
+    /// Fixed-width big-endian integers whose byte order matches their numeric order.
+    /// Signed integers have their sign bit flipped so that negatives sort first.
+    /// The layout is fixed regardless of the host's native endianness.
+    FixedBigEndian,
+}
+
+impl Default for IntEncoding {
+    fn default() -> Self {
+        IntEncoding::Varint
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrEncoding {
+    LengthPrefixed,
+    /// Zero bytes are escaped as `0x00 0xFF` and the value is terminated by `0x00 0x01`,
+    /// so that byte order matches lexicographic order.
+    Escaped,
+    /// C-style strings terminated by a single `0x00`. Values containing a zero byte are rejected.
+    NulTerminated,
+}
+
+impl Default for StrEncoding {
+    fn default() -> Self {
+        StrEncoding::LengthPrefixed
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeqEncoding {
+    LengthPrefixed,
+    /// Every element is preceded by `0x01` and the sequence is terminated by `0x00`,
+    /// so that byte order matches lexicographic order.
+    Terminated,
+    /// Elements are written in chunks, each preceded by its element count, and the sequence is
+    /// terminated by an empty chunk. Sequences of unknown length are streamed a chunk at a time
+    /// rather than buffered whole. Maps are written as a single chunk.
+    ///
+    /// Decoding accepts any split into chunks, so unlike the other encodings this one is not
+    /// canonical.
+    Chunked,
+}
+
+impl Default for SeqEncoding {
+    fn default() -> Self {
+        SeqEncoding::LengthPrefixed
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StructEncoding {
+    /// Fields are written one after the other, with nothing to mark how many there are.
+    Positional,
+    /// The field count is written before the fields. Decoding accepts fewer fields than
+    /// declared, leaving trailing fields to their `#[serde(default)]`.
+    LengthPrefixed,
+    /// The field count is written before the fields, and must match the declared count
+    /// exactly on decode.
+    StrictLengthPrefixed,
+}
+
+impl Default for StructEncoding {
+    fn default() -> Self {
+        StructEncoding::Positional
+    }
+}
+
+/// Bytes that dictionary back-references may repeat per message unless
+/// [`Options::max_dictionary_expansion`] says otherwise.
+pub const DEFAULT_MAX_DICTIONARY_EXPANSION: usize = 16 << 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    pub(crate) int_encoding: IntEncoding,
+    pub(crate) str_encoding: StrEncoding,
+    pub(crate) seq_encoding: SeqEncoding,
+    pub(crate) struct_encoding: StructEncoding,
+    pub(crate) max_collection_len: Option<usize>,
+    pub(crate) allow_unsized_seq: bool,
+    pub(crate) max_str_len: Option<usize>,
+    pub(crate) string_dictionary: bool,
+    pub(crate) max_dictionary_expansion: usize,
+    pub(crate) lenient_bool: bool,
+    pub(crate) max_output_len: Option<usize>,
+    pub(crate) fixed_variant_index: bool,
+    pub(crate) trust_key_ord: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            int_encoding: IntEncoding::default(),
+            str_encoding: StrEncoding::default(),
+            seq_encoding: SeqEncoding::default(),
+            struct_encoding: StructEncoding::default(),
+            max_collection_len: None,
+            allow_unsized_seq: false,
+            max_str_len: None,
+            string_dictionary: false,
+            max_dictionary_expansion: DEFAULT_MAX_DICTIONARY_EXPANSION,
+            lenient_bool: false,
+            max_output_len: None,
+            fixed_variant_index: false,
+            trust_key_ord: false,
+        }
+    }
+}
+
+impl Options {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Encoding under which the byte order of two values matches their logical order,
+    /// for use as range-scannable keys.
+    pub fn order_preserving() -> Self {
+        Self::new()
+            .int_encoding(IntEncoding::FixedBigEndian)
+            .str_encoding(StrEncoding::Escaped)
+            .seq_encoding(SeqEncoding::Terminated)
+    }
+
+    pub fn int_encoding(mut self, int_encoding: IntEncoding) -> Self {
+        self.int_encoding = int_encoding;
+        self
+    }
+
+    pub fn str_encoding(mut self, str_encoding: StrEncoding) -> Self {
+        self.str_encoding = str_encoding;
+        self
+    }
+
+    pub fn seq_encoding(mut self, seq_encoding: SeqEncoding) -> Self {
+        self.seq_encoding = seq_encoding;
+        self
+    }
+
+    pub fn struct_encoding(mut self, struct_encoding: StructEncoding) -> Self {
+        self.struct_encoding = struct_encoding;
+        self
+    }
+
+    /// Rejects sequences and sets holding more than `max_collection_len` elements on decode.
+    pub fn max_collection_len(mut self, max_collection_len: usize) -> Self {
+        self.max_collection_len = Some(max_collection_len);
+        self
+    }
+
+    /// Rejects strings longer than `max_str_len` bytes on decode. Byte arrays are not affected.
+    pub fn max_str_len(mut self, max_str_len: usize) -> Self {
+        self.max_str_len = Some(max_str_len);
+        self
+    }
+
+    /// Accepts sequences whose length is unknown upfront, such as those produced by
+    /// `Serializer::collect_seq` over a filtering iterator. Their elements are buffered in
+    /// memory until the sequence ends so that the length prefix can be written first.
+    pub fn allow_unsized_seq(mut self) -> Self {
+        self.allow_unsized_seq = true;
+        self
+    }
+
+    /// Shorthand for [`SeqEncoding::Chunked`], which streams sequences of unknown length
+    /// without buffering them whole.
+    pub fn streaming_seq(self) -> Self {
+        self.seq_encoding(SeqEncoding::Chunked)
+    }
+
+    /// Aborts serialization with `OutputLimitExceeded` once the output would grow past
+    /// `max_output_len` bytes. The buffers holding maps and sequences of unknown length count
+    /// towards the limit as they fill, so neither grows past it before being written out.
+    pub fn max_output_len(mut self, max_output_len: usize) -> Self {
+        self.max_output_len = Some(max_output_len);
+        self
+    }
+
+    /// Writes enum variant indices as a little-endian `u32`, so that the data following them
+    /// lies at a fixed offset. This takes precedence over `int_encoding`.
+    pub fn fixed_variant_index(mut self) -> Self {
+        self.fixed_variant_index = true;
+        self
+    }
+
+    /// Writes map entries in the order the map iterates them, rather than sorting them by their
+    /// encoded keys, and accepts entries in any order on decode. Maps of known length are then
+    /// written without buffering. Decoding still rejects a key that repeats an earlier one.
+    ///
+    /// The output is deterministic only for maps that iterate in a deterministic order, such as
+    /// `BTreeMap`, and is not canonical: maps whose keys order differently than their encodings
+    /// are written differently than under the default options.
+    pub fn trust_key_ord(mut self) -> Self {
+        self.trust_key_ord = true;
+        self
+    }
+
+    /// Writes each distinct string in full only once per message. Every string is preceded by
+    /// a varint: `0` for a string written in full, or `n` to repeat the `n`-th such string.
+    /// Strings within map entries are always written in full.
+    ///
+    /// On decode, a string written in full more than once is rejected, and the strings that
+    /// back-references repeat may total at most
+    /// [`max_dictionary_expansion`](Self::max_dictionary_expansion) bytes.
+    pub fn string_dictionary(mut self) -> Self {
+        self.string_dictionary = true;
+        self
+    }
+
+    /// Rejects messages whose dictionary back-references together repeat more than
+    /// `max_dictionary_expansion` bytes of strings on decode. Defaults to
+    /// [`DEFAULT_MAX_DICTIONARY_EXPANSION`].
+    pub fn max_dictionary_expansion(mut self, max_dictionary_expansion: usize) -> Self {
+        self.max_dictionary_expansion = max_dictionary_expansion;
+        self
+    }
+
+    /// Decodes any nonzero boolean or option tag as `true` or `Some`, rather than rejecting
+    /// tags other than `0` and `1`. Such input is not canonical, and exists only to accept data
+    /// from non-conforming encoders.
+    pub fn lenient_bool(mut self) -> Self {
+        self.lenient_bool = true;
+        self
+    }
+
+    // Whether values are encoded as under the default options, from which the limits differ
+    // only in what they accept
+    pub(crate) fn encodes_like_default(&self) -> bool {
+        let limits = Options {
+            max_collection_len: None,
+            allow_unsized_seq: false,
+            max_str_len: None,
+            max_dictionary_expansion: DEFAULT_MAX_DICTIONARY_EXPANSION,
+            max_output_len: None,
+            ..*self
+        };
+        limits == Options::default()
+    }
+}
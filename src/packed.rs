@@ -0,0 +1,189 @@
+//! Encodes a `Vec` of integers in bulk, for use with `#[serde(with = "cord::packed")]`.
+//!
+//! Under [`IntEncoding::FixedBigEndian`] with length-prefixed sequences, every element has the
+//! same width, so the array is written as its element count followed by the elements as
+//! contiguous little-endian bytes, and the decoder reads it back as one slice rather than one
+//! element at a time. Unlike the other fixed-width integers, the elements are therefore not
+//! ordered by their bytes. Under other options, and in other formats, the `Vec` is encoded as
+//! usual.
+//!
+//! [`IntEncoding::FixedBigEndian`]: crate::IntEncoding::FixedBigEndian
+
+use crate::fixint::FixedInt;
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+use std::fmt::Formatter;
+use std::marker::PhantomData;
+
+// Reserved names through which a packed `Vec` asks Cord's (de)serializer to write its elements
+// as little-endian bytes and read them back as one slice, by element width
+const PACKED_2: &str = "$cord::Packed2";
+const PACKED_4: &str = "$cord::Packed4";
+const PACKED_8: &str = "$cord::Packed8";
+
+pub(crate) fn packed_width(name: &str) -> Option<usize> {
+    match name {
+        PACKED_2 => Some(2),
+        PACKED_4 => Some(4),
+        PACKED_8 => Some(8),
+        _ => None,
+    }
+}
+
+mod private {
+    pub trait Sealed: Sized {
+        const NAME: &'static str;
+
+        fn decode(bytes: &[u8]) -> Option<Self>;
+    }
+}
+
+/// Integers whose `Vec` can be encoded in bulk.
+pub trait PackedInt: private::Sealed {}
+
+macro_rules! impl_packed {
+    ($(($int:ty, $name:ident)),*) => {
+        $(
+            impl private::Sealed for $int {
+                const NAME: &'static str = $name;
+
+                fn decode(bytes: &[u8]) -> Option<Self> {
+                    <$int as FixedInt>::decode_le(bytes)
+                }
+            }
+
+            impl PackedInt for $int {}
+        )*
+    };
+}
+
+impl_packed!(
+    (u16, PACKED_2),
+    (i16, PACKED_2),
+    (u32, PACKED_4),
+    (i32, PACKED_4),
+    (u64, PACKED_8),
+    (i64, PACKED_8)
+);
+
+pub fn serialize<S, T>(value: &[T], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Serialize + PackedInt,
+{
+    serializer.serialize_newtype_struct(T::NAME, value)
+}
+
+pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + PackedInt,
+{
+    deserializer.deserialize_newtype_struct(
+        T::NAME,
+        PackedVisitor {
+            marker: PhantomData,
+        },
+    )
+}
+
+struct PackedVisitor<T> {
+    marker: PhantomData<fn() -> Vec<T>>,
+}
+
+impl<'de, T> Visitor<'de> for PackedVisitor<T>
+where
+    T: Deserialize<'de> + PackedInt,
+{
+    type Value = Vec<T>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("a sequence of integers")
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Vec::deserialize(deserializer)
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let width = std::mem::size_of::<T>();
+        if v.len() % width != 0 {
+            return Err(de::Error::invalid_length(v.len(), &self));
+        }
+        v.chunks_exact(width)
+            .map(|chunk| T::decode(chunk).ok_or_else(|| de::Error::invalid_length(v.len(), &self)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        deserialize, deserialize_with_options, serialize, serialize_with_options, CordError,
+        IntEncoding, Options,
+    };
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Samples {
+        #[serde(with = "crate::packed")]
+        unsigned: Vec<u32>,
+        #[serde(with = "crate::packed")]
+        signed: Vec<i16>,
+    }
+
+    fn samples() -> Samples {
+        Samples {
+            unsigned: vec![1, 0x0102_0304],
+            signed: vec![-1, 2],
+        }
+    }
+
+    #[test]
+    fn packed_fixed() {
+        let options = Options::new().int_encoding(IntEncoding::FixedBigEndian);
+        let bytes = serialize_with_options(&samples(), options).unwrap();
+        assert_eq!(
+            bytes,
+            [2, 1, 0, 0, 0, 4, 3, 2, 1, 2, 0xFF, 0xFF, 0x02, 0x00]
+        );
+        assert_eq!(
+            deserialize_with_options::<Samples>(&bytes, options).unwrap(),
+            samples()
+        );
+        assert_eq!(
+            deserialize_with_options::<Samples>(&bytes[..12], options)
+                .unwrap_err()
+                .root_cause(),
+            &CordError::ValidationError("Unexpected end of stream")
+        );
+    }
+
+    #[test]
+    fn packed_fixed_round_trip() {
+        let options = Options::new().int_encoding(IntEncoding::FixedBigEndian);
+        let samples = Samples {
+            unsigned: (0..1000).map(|index| index * 0x0001_0203).collect(),
+            signed: vec![i16::MIN, -1, 0, 1, i16::MAX],
+        };
+        let bytes = serialize_with_options(&samples, options).unwrap();
+        assert_eq!(bytes.len(), 2 + 4000 + 1 + 10);
+        assert_eq!(
+            deserialize_with_options::<Samples>(&bytes, options).unwrap(),
+            samples
+        );
+    }
+
+    #[test]
+    fn packed_varint() {
+        let bytes = serialize(&samples()).unwrap();
+        assert_eq!(bytes, [2, 1, 132, 134, 136, 8, 2, 1, 4]);
+        assert_eq!(deserialize::<Samples>(&bytes).unwrap(), samples());
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{deserialize, serialize, CordError, CordResult};
+use integer_encoding::VarInt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Serializes `pairs` as a sequence of `(K, V)` tuples sorted by the encoding of their keys,
+/// so that equal maps produce equal bytes regardless of their iteration order. Duplicate keys
+/// are rejected. Decode with [`deserialize_map_from_pairs`] or into a `Vec<(K, V)>`.
+pub fn serialize_map_as_pairs<I, K, V>(pairs: I) -> CordResult<Vec<u8>>
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: Serialize,
+    V: Serialize,
+{
+    let mut entries = pairs
+        .into_iter()
+        .map(|(key, value)| Ok((serialize(&key)?, serialize(&value)?)))
+        .collect::<CordResult<Vec<_>>>()?;
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    if entries.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+        return Err(CordError::ValidationError("Duplicate map key"));
+    }
+
+    let mut output = entries.len().encode_var_vec();
+    for (key, value) in entries {
+        output.extend_from_slice(&2_usize.encode_var_vec());
+        output.extend_from_slice(&key);
+        output.extend_from_slice(&value);
+    }
+    Ok(output)
+}
+
+/// Decodes a map written by [`serialize_map_as_pairs`], rejecting pairs that are not sorted
+/// by strictly ascending key encodings.
+pub fn deserialize_map_from_pairs<'a, K, V>(bytes: &'a [u8]) -> CordResult<HashMap<K, V>>
+where
+    K: Deserialize<'a> + Serialize + Hash + Eq,
+    V: Deserialize<'a>,
+{
+    let pairs: Vec<(K, V)> = deserialize(bytes)?;
+    let mut map = HashMap::with_capacity(pairs.len());
+    let mut previous_key: Option<Vec<u8>> = None;
+    for (key, value) in pairs {
+        let current_key = Some(serialize(&key)?);
+        if previous_key.is_some() && previous_key >= current_key {
+            return Err(CordError::ValidationError("Unordered map keys"));
+        }
+
+        previous_key = current_key;
+        map.insert(key, value);
+    }
+    Ok(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{deserialize_map_from_pairs, serialize_map_as_pairs};
+    use crate::{deserialize, CordError};
+    use std::collections::HashMap;
+
+    #[test]
+    fn map_as_pairs_roundtrip() {
+        let map: HashMap<String, u16> = HashMap::from([
+            (String::from("b"), 300),
+            (String::from("aa"), 1),
+            (String::from("z"), 2),
+        ]);
+
+        let bytes = serialize_map_as_pairs(&map).unwrap();
+        assert_eq!(bytes, [3, 2, 1, 98, 172, 2, 2, 1, 122, 2, 2, 2, 97, 97, 1]);
+        assert_eq!(
+            deserialize_map_from_pairs::<String, u16>(&bytes).unwrap(),
+            map
+        );
+        assert_eq!(
+            deserialize::<Vec<(String, u16)>>(&bytes).unwrap(),
+            vec![
+                (String::from("b"), 300),
+                (String::from("z"), 2),
+                (String::from("aa"), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn map_as_pairs_rejects_duplicate_keys() {
+        assert_eq!(
+            serialize_map_as_pairs(vec![("a", 1), ("a", 2)]).unwrap_err(),
+            CordError::ValidationError("Duplicate map key")
+        );
+
+        let input: Vec<u8> = vec![2, 2, 1, 97, 1, 2, 1, 97, 2];
+        assert_eq!(
+            deserialize_map_from_pairs::<String, u8>(&input).unwrap_err(),
+            CordError::ValidationError("Unordered map keys")
+        );
+    }
+
+    #[test]
+    fn map_as_pairs_rejects_unordered_keys() {
+        let input: Vec<u8> = vec![2, 2, 1, 122, 1, 2, 1, 97, 2];
+        assert_eq!(
+            deserialize_map_from_pairs::<String, u8>(&input).unwrap_err(),
+            CordError::ValidationError("Unordered map keys")
+        );
+    }
+}
@@ -0,0 +1,136 @@
+//! Records for append-only logs, each laid out as `[length][cord bytes][checksum]` so that a
+//! scan can tell a truncated record apart from a corrupted one.
+//!
+//! The length is a varint counting the Cord bytes alone, and the checksum is a big-endian
+//! `u32` computed over them. Both ends must agree on the [`Checksum`] algorithm.
+
+use crate::de::checked_len_add;
+use crate::frame::MAX_LENGTH_PREFIX;
+use crate::{deserialize, serialize, CordError, CordResult};
+use integer_encoding::VarInt;
+use serde::{Deserialize, Serialize};
+use std::convert::TryInto;
+use std::io::Write;
+
+const CHECKSUM_LEN: usize = 4;
+
+/// The algorithm computing a record's checksum, each behind the feature of the same name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Checksum {
+    #[cfg(feature = "crc32")]
+    Crc32,
+    /// xxHash32 with a seed of zero.
+    #[cfg(feature = "xxhash")]
+    Xxhash,
+}
+
+impl Checksum {
+    fn compute(self, bytes: &[u8]) -> u32 {
+        match self {
+            #[cfg(feature = "crc32")]
+            Checksum::Crc32 => crc32fast::hash(bytes),
+            #[cfg(feature = "xxhash")]
+            Checksum::Xxhash => xxhash_rust::xxh32::xxh32(bytes, 0),
+        }
+    }
+}
+
+pub fn write_record<W, T>(writer: &mut W, value: &T, checksum: Checksum) -> CordResult<()>
+where
+    W: ?Sized + Write,
+    T: ?Sized + Serialize,
+{
+    let body = serialize(value)?;
+    writer.write_all(&body.len().encode_var_vec())?;
+    writer.write_all(&body)?;
+    writer.write_all(&checksum.compute(&body).to_be_bytes())?;
+    Ok(())
+}
+
+/// Decodes the record at the start of `buffer`, returning it along with the number of bytes it
+/// occupied, so that the next record starts right after.
+pub fn read_record<'a, T>(buffer: &'a [u8], checksum: Checksum) -> CordResult<(T, usize)>
+where
+    T: Deserialize<'a>,
+{
+    let (len, prefix) = match usize::decode_var(buffer) {
+        Some(decoded) => decoded,
+        None if buffer.len() >= MAX_LENGTH_PREFIX => {
+            return Err(CordError::ValidationError("Invalid varint"))
+        }
+        None => return Err(CordError::ValidationError("Record extends past buffer")),
+    };
+    let body_end = checked_len_add(prefix, len)?;
+    let end = checked_len_add(body_end, CHECKSUM_LEN)?;
+    if buffer.len() < end {
+        return Err(CordError::ValidationError("Record extends past buffer"));
+    }
+
+    let body = &buffer[prefix..body_end];
+    let expected = u32::from_be_bytes(buffer[body_end..end].try_into().unwrap());
+    if checksum.compute(body) != expected {
+        return Err(CordError::ValidationError("Checksum mismatch"));
+    }
+    Ok((deserialize(body)?, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_record, write_record, Checksum};
+    use crate::CordError;
+
+    fn checksums() -> Vec<Checksum> {
+        vec![
+            #[cfg(feature = "crc32")]
+            Checksum::Crc32,
+            #[cfg(feature = "xxhash")]
+            Checksum::Xxhash,
+        ]
+    }
+
+    #[cfg(feature = "crc32")]
+    #[test]
+    fn write_record_crc32() {
+        let mut log = Vec::new();
+        write_record(&mut log, "test", Checksum::Crc32).unwrap();
+        let crc = crc32fast::hash(&[4, 116, 101, 115, 116]).to_be_bytes();
+        assert_eq!(log[..6], [5, 4, 116, 101, 115, 116]);
+        assert_eq!(log[6..], crc);
+    }
+
+    #[test]
+    fn read_records_in_sequence() {
+        for checksum in checksums() {
+            let mut log = Vec::new();
+            write_record(&mut log, &1_u8, checksum).unwrap();
+            write_record(&mut log, "second", checksum).unwrap();
+
+            let (first, consumed) = read_record::<u8>(&log, checksum).unwrap();
+            assert_eq!((first, consumed), (1, 6));
+            let (second, rest) = read_record::<&str>(&log[consumed..], checksum).unwrap();
+            assert_eq!(second, "second");
+            assert_eq!(consumed + rest, log.len());
+        }
+    }
+
+    #[test]
+    fn read_record_detects_truncation_and_corruption() {
+        for checksum in checksums() {
+            let mut log = Vec::new();
+            write_record(&mut log, "test", checksum).unwrap();
+
+            for len in 0..log.len() {
+                assert_eq!(
+                    read_record::<String>(&log[..len], checksum).unwrap_err(),
+                    CordError::ValidationError("Record extends past buffer")
+                );
+            }
+
+            log[3] ^= 1;
+            assert_eq!(
+                read_record::<String>(&log, checksum).unwrap_err(),
+                CordError::ValidationError("Checksum mismatch")
+            );
+        }
+    }
+}
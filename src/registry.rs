@@ -0,0 +1,208 @@
+use crate::{deserialize, deserialize_counted, serialize, serialize_into};
+use crate::{CordError, CordResult};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+type Encode = Box<dyn Fn(&dyn Any, &mut Vec<u8>) -> CordResult<()>>;
+type Decode<T> = Box<dyn Fn(&[u8]) -> CordResult<Box<T>>>;
+
+/// Encodes trait objects such as `Box<dyn Plugin>` as a varint tag identifying the concrete
+/// type, followed by that type's own encoding.
+///
+/// Every concrete type must be registered under the same tag wherever it is encoded or
+/// decoded; encoding an unregistered type or decoding an unknown tag fails. Tags, like variant
+/// indices, must never be reassigned once values using them have been written.
+///
+/// ```
+/// use cord::Registry;
+/// use serde::{Deserialize, Serialize};
+/// use std::any::Any;
+///
+/// trait Shape: Any {
+///     fn as_any(&self) -> &dyn Any;
+/// }
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Circle {
+///     radius: u32,
+/// }
+///
+/// impl Shape for Circle {
+///     fn as_any(&self) -> &dyn Any {
+///         self
+///     }
+/// }
+///
+/// let mut registry = Registry::<dyn Shape>::new(|shape| shape.as_any());
+/// registry.register(1, |circle: Circle| Box::new(circle)).unwrap();
+///
+/// let shape: Box<dyn Shape> = Box::new(Circle { radius: 2 });
+/// let bytes = registry.serialize(&*shape).unwrap();
+/// assert_eq!(bytes, [1, 2]);
+/// assert!(registry.deserialize(&bytes).is_ok());
+/// ```
+pub struct Registry<T: ?Sized> {
+    as_any: fn(&T) -> &dyn Any,
+    tags: HashMap<TypeId, (u32, Encode)>,
+    decoders: HashMap<u32, Decode<T>>,
+}
+
+impl<T: ?Sized + 'static> Registry<T> {
+    /// Creates an empty registry. `as_any` exposes the concrete type behind a trait object.
+    pub fn new(as_any: fn(&T) -> &dyn Any) -> Self {
+        Self {
+            as_any,
+            tags: HashMap::new(),
+            decoders: HashMap::new(),
+        }
+    }
+
+    /// Registers the concrete type `C` under `tag`, with `wrap` boxing it as a trait object.
+    pub fn register<C>(&mut self, tag: u32, wrap: fn(C) -> Box<T>) -> CordResult<()>
+    where
+        C: Serialize + DeserializeOwned + 'static,
+    {
+        if self.decoders.contains_key(&tag) || self.tags.contains_key(&TypeId::of::<C>()) {
+            return Err(CordError::ValidationError("Type or tag already registered"));
+        }
+        let encode: Encode = Box::new(|value, output| match value.downcast_ref::<C>() {
+            Some(value) => serialize_into(output, value),
+            None => Err(CordError::ValidationError("Unregistered type")),
+        });
+        self.tags.insert(TypeId::of::<C>(), (tag, encode));
+        self.decoders.insert(
+            tag,
+            Box::new(move |bytes| deserialize::<C>(bytes).map(wrap)),
+        );
+        Ok(())
+    }
+
+    pub fn serialize(&self, value: &T) -> CordResult<Vec<u8>> {
+        let value = (self.as_any)(value);
+        let (tag, encode) = self
+            .tags
+            .get(&value.type_id())
+            .ok_or(CordError::ValidationError("Unregistered type"))?;
+        let mut output = serialize(tag)?;
+        encode(value, &mut output)?;
+        Ok(output)
+    }
+
+    pub fn deserialize(&self, bytes: &[u8]) -> CordResult<Box<T>> {
+        let (tag, consumed) = deserialize_counted::<u32>(bytes)?;
+        let decode = self
+            .decoders
+            .get(&tag)
+            .ok_or(CordError::ValidationError("Unknown type tag"))?;
+        decode(&bytes[consumed..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Registry;
+    use crate::CordError;
+    use serde::{Deserialize, Serialize};
+    use std::any::Any;
+
+    trait Plugin: Any {
+        fn as_any(&self) -> &dyn Any;
+        fn name(&self) -> String;
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Echo {
+        prefix: String,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Counter(u8);
+
+    #[derive(Serialize, Deserialize)]
+    struct Unregistered;
+
+    impl Plugin for Echo {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn name(&self) -> String {
+            format!("echo {}", self.prefix)
+        }
+    }
+
+    impl Plugin for Counter {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn name(&self) -> String {
+            format!("counter {}", self.0)
+        }
+    }
+
+    impl Plugin for Unregistered {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn name(&self) -> String {
+            String::from("unregistered")
+        }
+    }
+
+    fn registry() -> Registry<dyn Plugin> {
+        let mut registry = Registry::<dyn Plugin>::new(|plugin| plugin.as_any());
+        registry.register(1, |echo: Echo| Box::new(echo)).unwrap();
+        registry
+            .register(300, |counter: Counter| Box::new(counter))
+            .unwrap();
+        registry
+    }
+
+    #[test]
+    fn registry_roundtrip() {
+        let registry = registry();
+        let plugins: Vec<Box<dyn Plugin>> = vec![
+            Box::new(Echo {
+                prefix: String::from("a"),
+            }),
+            Box::new(Counter(7)),
+        ];
+
+        let bytes = registry.serialize(&*plugins[0]).unwrap();
+        assert_eq!(bytes, [1, 1, 97]);
+        assert_eq!(registry.deserialize(&bytes).unwrap().name(), "echo a");
+
+        let bytes = registry.serialize(&*plugins[1]).unwrap();
+        assert_eq!(bytes, [172, 2, 7]);
+        assert_eq!(registry.deserialize(&bytes).unwrap().name(), "counter 7");
+    }
+
+    #[test]
+    fn registry_rejects_unknown_types() {
+        let mut registry = registry();
+        assert_eq!(
+            registry.serialize(&Unregistered).unwrap_err(),
+            CordError::ValidationError("Unregistered type")
+        );
+        assert_eq!(
+            registry.deserialize(&[2]).err(),
+            Some(CordError::ValidationError("Unknown type tag"))
+        );
+        assert_eq!(
+            registry.deserialize(&[1, 1, 97, 0]).err(),
+            Some(CordError::ValidationError("Unexpected trailing bytes"))
+        );
+        assert_eq!(
+            registry.register(1, |unregistered: Unregistered| Box::new(unregistered)),
+            Err(CordError::ValidationError("Type or tag already registered"))
+        );
+        assert_eq!(
+            registry.register(2, |counter: Counter| Box::new(counter)),
+            Err(CordError::ValidationError("Type or tag already registered"))
+        );
+    }
+}
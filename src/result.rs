@@ -16,6 +16,46 @@ pub enum CordError {
     SerializationError(String),
     #[error("Cord deserialization error: {0}")]
     DeserializationError(String),
+    #[error("Cord buffer is full: {needed} bytes needed, {capacity} available")]
+    BufferFull { needed: usize, capacity: usize },
+    #[error("Cord could not parse {input:?}: {detail}")]
+    ParseError { input: String, detail: String },
+    #[error("Cord output exceeds limit of {limit} bytes")]
+    OutputLimitExceeded { limit: usize },
+    #[error("{source} at {}", path.join("."))]
+    AtPath {
+        path: Vec<&'static str>,
+        source: Box<CordError>,
+    },
+}
+
+impl CordError {
+    pub(crate) fn in_field(self, field: &'static str) -> Self {
+        match self {
+            CordError::AtPath { mut path, source } => {
+                path.insert(0, field);
+                CordError::AtPath { path, source }
+            }
+            error => CordError::AtPath {
+                path: vec![field],
+                source: Box::new(error),
+            },
+        }
+    }
+
+    pub fn path(&self) -> &[&'static str] {
+        match self {
+            CordError::AtPath { path, .. } => path,
+            _ => &[],
+        }
+    }
+
+    pub fn root_cause(&self) -> &CordError {
+        match self {
+            CordError::AtPath { source, .. } => source,
+            error => error,
+        }
+    }
 }
 
 impl From<std::io::Error> for CordError {
@@ -0,0 +1,80 @@
+use serde::{ser, Deserialize, Deserializer, Serializer};
+
+/// Encodes an `f64` as an `i64` holding the value scaled by `10^DIGITS`, for use with
+/// `#[serde(with = "cord::Scaled::<2>")]`.
+///
+/// Values are rounded half away from zero to `DIGITS` decimal places. Non-finite values and
+/// values whose scaled form does not fit an `i64` fail to serialize. Decoding divides by the
+/// same factor, so it is exact only while the scaled integer is within `±2^53`.
+pub struct Scaled<const DIGITS: u32>;
+
+impl<const DIGITS: u32> Scaled<DIGITS> {
+    fn factor() -> f64 {
+        10_f64.powi(DIGITS as i32)
+    }
+
+    pub fn serialize<S>(value: &f64, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let scaled = (value * Self::factor()).round();
+        // `i64::MAX as f64` rounds up to 2^63, which is itself out of range
+        if !scaled.is_finite() || scaled < i64::MIN as f64 || scaled >= i64::MAX as f64 {
+            return Err(ser::Error::custom("scaled value out of range"));
+        }
+        serializer.serialize_i64(scaled as i64)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<f64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        i64::deserialize(deserializer).map(|scaled| scaled as f64 / Self::factor())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{deserialize, serialize};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Price {
+        #[serde(with = "crate::Scaled::<2>")]
+        amount: f64,
+    }
+
+    #[test]
+    fn scaled_roundtrip() {
+        let price = Price { amount: 19.99 };
+        let bytes = serialize(&price).unwrap();
+        assert_eq!(bytes, serialize(&1999_i64).unwrap());
+        assert_eq!(deserialize::<Price>(&bytes).unwrap(), price);
+
+        let price = Price { amount: -0.5 };
+        assert_eq!(serialize(&price).unwrap(), serialize(&-50_i64).unwrap());
+    }
+
+    #[test]
+    fn scaled_rounds_half_away_from_zero() {
+        assert_eq!(
+            serialize(&Price { amount: 0.125 }).unwrap(),
+            serialize(&13_i64).unwrap()
+        );
+        assert_eq!(
+            serialize(&Price { amount: -0.125 }).unwrap(),
+            serialize(&-13_i64).unwrap()
+        );
+    }
+
+    #[test]
+    fn scaled_out_of_range() {
+        assert!(serialize(&Price { amount: f64::NAN }).is_err());
+        assert!(serialize(&Price {
+            amount: f64::INFINITY
+        })
+        .is_err());
+        assert!(serialize(&Price { amount: 1e17 }).is_err());
+        assert!(serialize(&Price { amount: 1e16 }).is_ok());
+    }
+}
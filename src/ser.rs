@@ -1,47 +1,386 @@
+use crate::embed::EMBED;
+use crate::fixint::FixedInt;
+use crate::options::{IntEncoding, Options, SeqEncoding, StrEncoding, StructEncoding};
+use crate::packed::packed_width;
 use crate::result::{CordError, CordResult};
+use crate::trace::trace;
+use crate::types::{RAW_BYTES, SET};
+use crate::wide;
+use crate::writer::{CountingWriter, HashWriter, LimitWriter, OutputBudget, SliceWriter};
 use integer_encoding::VarInt;
+use serde::ser::{SerializeSeq, SerializeStruct, SerializeTuple};
 use serde::{ser, Serialize, Serializer};
+use std::collections::HashMap;
+#[cfg(feature = "trace")]
+use std::{cell::Cell, rc::Rc};
 
 pub fn serialize<T>(value: &T) -> CordResult<Vec<u8>>
+where
+    T: ?Sized + Serialize,
+{
+    serialize_with_options(value, Options::default())
+}
+
+pub fn serialize_with_options<T>(value: &T, options: Options) -> CordResult<Vec<u8>>
+where
+    T: ?Sized + Serialize,
+{
+    let mut output = Vec::new();
+    serialize_to(&mut output, value, options)?;
+    Ok(output)
+}
+
+/// The version of the wire format, written ahead of the value by [`serialize_versioned`].
+pub const FORMAT_VERSION: u8 = 1;
+
+/// Serializes `value` preceded by a single [`FORMAT_VERSION`] byte, so that persisted data
+/// records the format it was written in.
+pub fn serialize_versioned<T>(value: &T) -> CordResult<Vec<u8>>
+where
+    T: ?Sized + Serialize,
+{
+    let mut output = vec![FORMAT_VERSION];
+    serialize_to(&mut output, value, Options::default())?;
+    Ok(output)
+}
+
+/// The number of bytes `value` occupies as a varint.
+pub fn varint_len(value: u64) -> usize {
+    value.required_space()
+}
+
+/// The number of bytes `value` occupies as a zigzag-encoded varint.
+pub fn signed_varint_len(value: i64) -> usize {
+    value.required_space()
+}
+
+/// The number of bytes taken by the prefix of a string, byte array or sequence of `len`.
+pub fn length_prefix_len(len: usize) -> usize {
+    len.required_space()
+}
+
+fn serialize_to<W, T>(writer: &mut W, value: &T, options: Options) -> CordResult<()>
+where
+    W: ?Sized + std::io::Write,
+    T: ?Sized + Serialize,
+{
+    let limit = match options.max_output_len {
+        Some(limit) => limit,
+        None => return serialize_within(writer, value, options, None),
+    };
+
+    let budget = OutputBudget::new(limit);
+    let mut writer = LimitWriter::new(writer, &budget);
+    let result = serialize_within(&mut writer, value, options, Some(&budget));
+    if budget.exceeded() {
+        return Err(CordError::OutputLimitExceeded { limit });
+    }
+    result
+}
+
+fn serialize_within<W, T>(
+    writer: &mut W,
+    value: &T,
+    options: Options,
+    budget: Option<&OutputBudget>,
+) -> CordResult<()>
+where
+    W: ?Sized + std::io::Write,
+    T: ?Sized + Serialize,
+{
+    let mut dictionary = Dictionary::new();
+    let mut serializer = CordSerializer::new(writer, options);
+    if options.string_dictionary {
+        serializer.dictionary = Some(&mut dictionary);
+    }
+    serializer.budget = budget;
+    value.serialize(serializer)
+}
+
+// Serializes `value` into `buffer` ahead of it being written out, charging its bytes to
+// `budget` so that buffering cannot outgrow `Options::max_output_len`
+fn serialize_buffered<T>(
+    buffer: &mut Vec<u8>,
+    value: &T,
+    options: Options,
+    dictionary: Option<&mut Dictionary>,
+    budget: Option<&OutputBudget>,
+) -> CordResult<()>
+where
+    T: ?Sized + Serialize,
+{
+    #[cfg(feature = "trace")]
+    let position = Rc::new(Cell::new(buffer.len()));
+    match budget {
+        Some(budget) => {
+            let mut writer = LimitWriter::new(buffer, budget);
+            let mut serializer = CordSerializer::new(&mut writer, options);
+            serializer.dictionary = dictionary;
+            serializer.budget = Some(budget);
+            #[cfg(feature = "trace")]
+            {
+                serializer.position = position;
+            }
+            value.serialize(serializer)
+        }
+        None => {
+            let mut serializer = CordSerializer::new(buffer, options);
+            serializer.dictionary = dictionary;
+            #[cfg(feature = "trace")]
+            {
+                serializer.position = position;
+            }
+            value.serialize(serializer)
+        }
+    }
+}
+
+/// Serializes successive values back to back into a shared buffer:
+/// `Encoder::new(&mut buffer).value(&x)?.value(&y)?;`
+#[derive(Debug)]
+pub struct Encoder<'a> {
+    output: &'a mut Vec<u8>,
+    options: Options,
+}
+
+impl<'a> Encoder<'a> {
+    pub fn new(output: &'a mut Vec<u8>) -> Self {
+        Self::with_options(output, Options::default())
+    }
+
+    pub fn with_options(output: &'a mut Vec<u8>, options: Options) -> Self {
+        Self { output, options }
+    }
+
+    /// Appends `value` to the buffer. On failure, the buffer is left as it was.
+    pub fn value<T>(&mut self, value: &T) -> CordResult<&mut Self>
+    where
+        T: ?Sized + Serialize,
+    {
+        let len = self.output.len();
+        if let Err(err) = serialize_to(self.output, value, self.options) {
+            self.output.truncate(len);
+            return Err(err);
+        }
+        Ok(self)
+    }
+}
+
+/// Serializes `items` as a sequence, producing the same bytes as serializing a `Vec<T>`
+/// holding them. Such output can be decoded back into a `Vec<T>`.
+pub fn serialize_slice<T>(items: &[T]) -> CordResult<Vec<u8>>
+where
+    T: Serialize,
+{
+    serialize(items)
+}
+
+pub fn serialize_into<W, T>(writer: &mut W, value: &T) -> CordResult<()>
+where
+    W: ?Sized + std::io::Write,
+    T: ?Sized + Serialize,
+{
+    value.serialize(CordSerializer::new(writer, Options::default()))
+}
+
+/// Serializes `value` into a `SmallVec`, which spills to the heap only for outputs longer than
+/// `N` bytes.
+#[cfg(feature = "smallvec")]
+pub fn serialize_small<const N: usize, T>(value: &T) -> CordResult<smallvec::SmallVec<[u8; N]>>
+where
+    T: ?Sized + Serialize,
+{
+    let mut output = smallvec::SmallVec::new();
+    serialize_into(&mut output, value)?;
+    Ok(output)
+}
+
+/// Serializes `value` into `writer`, returning the number of bytes written.
+pub fn serialize_into_counted<W, T>(writer: &mut W, value: &T) -> CordResult<usize>
+where
+    W: ?Sized + std::io::Write,
+    T: ?Sized + Serialize,
+{
+    let mut writer = CountingWriter::new(writer);
+    serialize_into(&mut writer, value)?;
+    Ok(writer.count())
+}
+
+/// Feeds the encoding of `value` to `hasher` as it is produced, without buffering it.
+///
+/// The encoding may reach the hasher over several `write` calls, so the result equals hashing
+/// [`serialize`]'s output only for hashers that do not depend on how their input is split, such
+/// as `DefaultHasher`. Cryptographic digests implementing `Write` can be passed to
+/// [`serialize_into`] directly.
+///
+/// If serialization fails partway, the hasher has already been fed the bytes produced before the
+/// error and should be discarded.
+pub fn hash_into<H, T>(hasher: &mut H, value: &T) -> CordResult<()>
+where
+    H: ?Sized + std::hash::Hasher,
+    T: ?Sized + Serialize,
+{
+    serialize_into(&mut HashWriter::new(hasher), value)
+}
+
+/// Serializes only the first `n_fields` fields of the struct `value`, for use as a key prefix.
+///
+/// Fields are positional, so the result is a prefix of `serialize(value)`. Values other than
+/// structs, or newtype structs around them, are serialized in full, including an `Option` or an
+/// enum variant holding a struct.
+pub fn serialize_prefix<T>(value: &T, n_fields: usize) -> CordResult<Vec<u8>>
 where
     T: ?Sized + Serialize,
 {
     let mut output = Vec::new();
-    value.serialize(CordSerializer::new(&mut output))?;
+    let mut serializer = CordSerializer::new(&mut output, Options::default());
+    serializer.prefix_fields = Some(n_fields);
+    value.serialize(serializer)?;
     Ok(output)
 }
 
+pub fn serialize_into_slice<T>(value: &T, buffer: &mut [u8]) -> CordResult<usize>
+where
+    T: ?Sized + Serialize,
+{
+    let mut writer = SliceWriter::new(buffer);
+    value.serialize(CordSerializer::new(&mut writer, Options::default()))?;
+
+    if writer.position() > writer.capacity() {
+        return Err(CordError::BufferFull {
+            needed: writer.position(),
+            capacity: writer.capacity(),
+        });
+    }
+    Ok(writer.position())
+}
+
+// Indices of the strings written so far in dictionary mode
+type Dictionary = HashMap<String, usize>;
+
 struct CordSerializer<'a, W: ?Sized> {
     output: &'a mut W,
+    options: Options,
+    dictionary: Option<&'a mut Dictionary>,
+    // Shared by every buffer under `Options::max_output_len`
+    budget: Option<&'a OutputBudget>,
+    // Fields of the outermost struct still to be written by `serialize_prefix`
+    prefix_fields: Option<usize>,
+    // Set within `RawBytes`, whose length is known from the schema, so that its tuple is
+    // written without a length and its bytes as they are
+    raw_bytes: bool,
+    // Set around a struct embedded in its parent, which omits its field count
+    embedded: bool,
+    // Set within a packed `Vec` under fixed-width integers, whose elements are written as
+    // little-endian bytes
+    packed: bool,
+    // Bytes written so far, for tracing
+    #[cfg(feature = "trace")]
+    position: Rc<Cell<usize>>,
 }
 
 impl<'a, W> CordSerializer<'a, W>
 where
     W: ?Sized + std::io::Write,
 {
-    fn new(output: &'a mut W) -> Self {
-        Self { output }
+    fn new(output: &'a mut W, options: Options) -> Self {
+        Self {
+            output,
+            options,
+            dictionary: None,
+            budget: None,
+            prefix_fields: None,
+            raw_bytes: false,
+            embedded: false,
+            packed: false,
+            #[cfg(feature = "trace")]
+            position: Rc::new(Cell::new(0)),
+        }
+    }
+
+    // Position within the output, for tracing. Within a map or a sequence of unknown length,
+    // which are buffered before being written out, it counts from the start of the buffer.
+    #[cfg(feature = "trace")]
+    fn offset(&self) -> usize {
+        self.position.get()
+    }
+
+    fn write_all(&mut self, bytes: &[u8]) -> CordResult<()> {
+        self.output.write_all(bytes)?;
+        #[cfg(feature = "trace")]
+        self.position.set(self.position.get() + bytes.len());
+        Ok(())
+    }
+
+    fn reborrow(&mut self) -> CordSerializer<'_, W> {
+        CordSerializer {
+            output: self.output,
+            options: self.options,
+            dictionary: self.dictionary.as_deref_mut(),
+            budget: self.budget,
+            prefix_fields: None,
+            raw_bytes: false,
+            embedded: false,
+            packed: false,
+            #[cfg(feature = "trace")]
+            position: self.position.clone(),
+        }
     }
 
     fn serialize_usize(&mut self, v: usize) -> CordResult<()> {
         self.write_varint(v)
     }
 
+    // Writes out bytes that were charged to the budget when they were buffered
+    fn write_buffered(&mut self, bytes: &[u8]) -> CordResult<()> {
+        if let Some(budget) = self.budget {
+            budget.refund(bytes.len());
+        }
+        self.write_all(bytes)
+    }
+
     fn serialize_variant_index(&mut self, v: u32) -> CordResult<()> {
-        self.write_varint(v)
+        trace!("serializing variant {v} at offset {}", self.offset());
+        if self.options.fixed_variant_index {
+            self.write_all(&v.to_le_bytes())?;
+            return Ok(());
+        }
+        self.write_int(v)
+    }
+
+    fn serialize_field_count(&mut self, len: usize) -> CordResult<()> {
+        match self.options.struct_encoding {
+            StructEncoding::Positional => Ok(()),
+            StructEncoding::LengthPrefixed | StructEncoding::StrictLengthPrefixed => {
+                self.serialize_usize(len)
+            }
+        }
     }
 
     fn write_varint<T: VarInt>(&mut self, v: T) -> CordResult<()> {
-        self.output.write_all(&v.encode_var_vec())?;
+        self.write_all(&v.encode_var_vec())?;
         Ok(())
     }
+
+    fn write_int<T: VarInt + FixedInt>(&mut self, v: T) -> CordResult<()> {
+        if self.packed {
+            return self.write_all(v.encode_le().as_ref());
+        }
+        match self.options.int_encoding {
+            IntEncoding::Varint => self.write_varint(v),
+            IntEncoding::FixedBigEndian => {
+                self.write_all(v.encode_fixed().as_ref())?;
+                Ok(())
+            }
+        }
+    }
 }
 
 macro_rules! serialize_varints {
     ($(($int:ty, $name:ident)),*) => {
         $(
             fn $name(mut self, v: $int) -> CordResult<()> {
-                self.write_varint(v)
+                self.write_int(v)
             }
         )*
     };
@@ -57,17 +396,17 @@ macro_rules! serialize_unsupported {
     };
 }
 
-impl<W> ser::Serializer for CordSerializer<'_, W>
+impl<'a, W> ser::Serializer for CordSerializer<'a, W>
 where
     W: ?Sized + std::io::Write,
 {
     type Ok = ();
     type Error = CordError;
-    type SerializeSeq = Self;
+    type SerializeSeq = SeqSerializer<'a, W>;
     type SerializeTuple = Self;
     type SerializeTupleStruct = Self;
     type SerializeTupleVariant = Self;
-    type SerializeMap = Self;
+    type SerializeMap = MapSerializer<'a, W>;
     type SerializeStruct = Self;
     type SerializeStructVariant = Self;
 
@@ -80,25 +419,75 @@ where
         (i16, serialize_i16),
         (i32, serialize_i32),
         (i64, serialize_i64),
-        (u8, serialize_u8),
         (u16, serialize_u16),
         (u32, serialize_u32),
         (u64, serialize_u64)
     );
 
+    fn serialize_u8(mut self, v: u8) -> CordResult<()> {
+        if self.raw_bytes {
+            self.write_all(&[v])?;
+            return Ok(());
+        }
+        self.write_int(v)
+    }
+
+    fn serialize_i128(mut self, v: i128) -> CordResult<()> {
+        match self.options.int_encoding {
+            IntEncoding::Varint => self.write_all(&wide::encode_i128(v))?,
+            IntEncoding::FixedBigEndian => self.write_all(v.encode_fixed().as_ref())?,
+        }
+        Ok(())
+    }
+
+    fn serialize_u128(mut self, v: u128) -> CordResult<()> {
+        match self.options.int_encoding {
+            IntEncoding::Varint => self.write_all(&wide::encode_u128(v))?,
+            IntEncoding::FixedBigEndian => self.write_all(v.encode_fixed().as_ref())?,
+        }
+        Ok(())
+    }
+
     serialize_unsupported!(
         (f32, serialize_f32),
         (f64, serialize_f64),
         (char, serialize_char)
     );
 
-    fn serialize_str(self, v: &str) -> CordResult<()> {
+    fn serialize_str(mut self, v: &str) -> CordResult<()> {
+        if let Some(dictionary) = &mut self.dictionary {
+            if let Some(index) = dictionary.get(v).copied() {
+                return self.serialize_usize(index + 1);
+            }
+            dictionary.insert(v.to_owned(), dictionary.len());
+            self.serialize_usize(0)?;
+        }
         self.serialize_bytes(v.as_bytes())
     }
 
     fn serialize_bytes(mut self, v: &[u8]) -> CordResult<()> {
-        self.serialize_usize(v.len())?;
-        self.output.write_all(v)?;
+        match self.options.str_encoding {
+            StrEncoding::LengthPrefixed => {
+                self.serialize_usize(v.len())?;
+                self.write_all(v)?;
+            }
+            StrEncoding::Escaped => {
+                for (index, part) in v.split(|byte| *byte == 0).enumerate() {
+                    if index > 0 {
+                        self.write_all(&[0, 0xff])?;
+                    }
+                    self.write_all(part)?;
+                }
+                self.write_all(&[0, 1])?;
+            }
+            StrEncoding::NulTerminated => {
+                if v.contains(&0) {
+                    return Err(CordError::ValidationError("Unexpected nul byte"));
+                }
+                self.write_all(v)?;
+                self.write_all(&[0])?;
+            }
+        }
         Ok(())
     }
 
@@ -106,11 +495,12 @@ where
         self.serialize_u8(0)
     }
 
-    fn serialize_some<T>(self, value: &T) -> CordResult<()>
+    fn serialize_some<T>(mut self, value: &T) -> CordResult<()>
     where
         T: ?Sized + Serialize,
     {
-        self.output.write_all(&[1])?;
+        self.write_all(&[1])?;
+        self.prefix_fields = None;
         value.serialize(self)
     }
 
@@ -123,21 +513,25 @@ where
         self.serialize_unit()
     }
 
-    #[allow(unused_mut)]
     fn serialize_unit_variant(
         mut self,
         _name: &'static str,
         variant_index: u32,
         _variant: &'static str,
     ) -> CordResult<()> {
-        self.serialize_u32(variant_index)
+        self.serialize_variant_index(variant_index)
     }
 
     #[allow(unused_mut)]
-    fn serialize_newtype_struct<T>(mut self, _name: &'static str, value: &T) -> CordResult<()>
+    fn serialize_newtype_struct<T>(mut self, name: &'static str, value: &T) -> CordResult<()>
     where
         T: ?Sized + Serialize,
     {
+        self.raw_bytes = name == RAW_BYTES;
+        self.embedded = name == EMBED;
+        self.packed = packed_width(name).is_some()
+            && self.options.int_encoding == IntEncoding::FixedBigEndian
+            && self.options.seq_encoding == SeqEncoding::LengthPrefixed;
         value.serialize(self)
     }
 
@@ -152,20 +546,37 @@ where
         T: ?Sized + Serialize,
     {
         self.serialize_variant_index(variant_index)?;
+        self.prefix_fields = None;
         value.serialize(self)
     }
 
     fn serialize_seq(mut self, len: Option<usize>) -> CordResult<Self::SerializeSeq> {
-        if let Some(len) = len {
-            self.serialize_usize(len)?;
-            Ok(self)
-        } else {
-            Err(CordError::NotSupported("unsized sequences"))
+        match (self.options.seq_encoding, len) {
+            (SeqEncoding::LengthPrefixed, Some(len)) => {
+                self.serialize_usize(len)?;
+                Ok(SeqSerializer::new(self, None))
+            }
+            (SeqEncoding::LengthPrefixed, None) if self.options.allow_unsized_seq => {
+                Ok(SeqSerializer::new(self, Some(Vec::new())))
+            }
+            (SeqEncoding::LengthPrefixed, None) => {
+                Err(CordError::NotSupported("unsized sequences"))
+            }
+            (SeqEncoding::Terminated, _) => Ok(SeqSerializer::new(self, None)),
+            (SeqEncoding::Chunked, Some(len)) => {
+                if len > 0 {
+                    self.serialize_usize(len)?;
+                }
+                Ok(SeqSerializer::new(self, None))
+            }
+            (SeqEncoding::Chunked, None) => Ok(SeqSerializer::new(self, Some(Vec::new()))),
         }
     }
 
     fn serialize_tuple(mut self, len: usize) -> CordResult<Self::SerializeTuple> {
-        self.serialize_usize(len)?;
+        if !self.raw_bytes {
+            self.serialize_usize(len)?;
+        }
         Ok(self)
     }
 
@@ -189,16 +600,30 @@ where
         Ok(self)
     }
 
-    fn serialize_map(self, _len: Option<usize>) -> CordResult<Self::SerializeMap> {
-        Err(CordError::NotSupported("map"))
+    fn serialize_map(mut self, len: Option<usize>) -> CordResult<Self::SerializeMap> {
+        match len {
+            Some(len) if self.options.trust_key_ord => {
+                match self.options.seq_encoding {
+                    SeqEncoding::LengthPrefixed => self.serialize_usize(len)?,
+                    SeqEncoding::Terminated => {}
+                    SeqEncoding::Chunked if len > 0 => self.serialize_usize(len)?,
+                    SeqEncoding::Chunked => {}
+                }
+                Ok(MapSerializer::streaming(self))
+            }
+            _ => Ok(MapSerializer::new(self, len.unwrap_or(0))),
+        }
     }
 
     #[allow(unused_mut)]
     fn serialize_struct(
         mut self,
         _name: &'static str,
-        _len: usize,
+        len: usize,
     ) -> CordResult<Self::SerializeStruct> {
+        if !self.embedded {
+            self.serialize_field_count(len)?;
+        }
         Ok(self)
     }
 
@@ -207,14 +632,53 @@ where
         _name: &'static str,
         variant_index: u32,
         _variant: &'static str,
-        _len: usize,
+        len: usize,
     ) -> CordResult<Self::SerializeStructVariant> {
         self.serialize_variant_index(variant_index)?;
+        self.serialize_field_count(len)?;
         Ok(self)
     }
 }
 
-impl<W> ser::SerializeSeq for CordSerializer<'_, W>
+// Sequences of unknown length are buffered until `end`, which writes the element count
+// followed by the buffered elements. Under `SeqEncoding::Chunked`, the buffer is instead
+// flushed as a chunk every `MAX_CHUNK_LEN` elements.
+const MAX_CHUNK_LEN: usize = 128;
+
+struct SeqSerializer<'a, W: ?Sized> {
+    serializer: CordSerializer<'a, W>,
+    buffer: Option<Vec<u8>>,
+    len: usize,
+}
+
+impl<'a, W> SeqSerializer<'a, W>
+where
+    W: ?Sized + std::io::Write,
+{
+    fn new(serializer: CordSerializer<'a, W>, buffer: Option<Vec<u8>>) -> Self {
+        Self {
+            serializer,
+            buffer,
+            len: 0,
+        }
+    }
+
+    fn chunked(&self) -> bool {
+        self.serializer.options.seq_encoding == SeqEncoding::Chunked
+    }
+
+    fn flush_chunk(&mut self) -> CordResult<()> {
+        if let Some(buffer) = &mut self.buffer {
+            self.serializer.serialize_usize(self.len)?;
+            self.serializer.write_buffered(buffer)?;
+            buffer.clear();
+            self.len = 0;
+        }
+        Ok(())
+    }
+}
+
+impl<W> ser::SerializeSeq for SeqSerializer<'_, W>
 where
     W: ?Sized + std::io::Write,
 {
@@ -225,11 +689,40 @@ where
     where
         T: ?Sized + Serialize,
     {
-        value.serialize(CordSerializer::new(self.output))
+        self.len += 1;
+        if let Some(buffer) = &mut self.buffer {
+            trace!("serializing element at offset {}", buffer.len());
+            serialize_buffered(
+                buffer,
+                value,
+                self.serializer.options,
+                self.serializer.dictionary.as_deref_mut(),
+                self.serializer.budget,
+            )?;
+            if self.chunked() && self.len == MAX_CHUNK_LEN {
+                self.flush_chunk()?;
+            }
+            return Ok(());
+        }
+        if self.serializer.options.seq_encoding == SeqEncoding::Terminated {
+            self.serializer.write_all(&[1])?;
+        }
+        trace!("serializing element at offset {}", self.serializer.offset());
+        let packed = self.serializer.packed;
+        let mut serializer = self.serializer.reborrow();
+        serializer.packed = packed;
+        value.serialize(serializer)
     }
 
-    fn end(self) -> CordResult<()> {
-        Ok(())
+    fn end(mut self) -> CordResult<()> {
+        if !self.chunked() || self.len > 0 {
+            self.flush_chunk()?;
+        }
+        match self.serializer.options.seq_encoding {
+            SeqEncoding::LengthPrefixed => Ok(()),
+            SeqEncoding::Terminated => self.serializer.write_all(&[0]),
+            SeqEncoding::Chunked => self.serializer.serialize_usize(0),
+        }
     }
 }
 
@@ -244,7 +737,10 @@ where
     where
         T: ?Sized + Serialize,
     {
-        value.serialize(CordSerializer::new(self.output))
+        let raw_bytes = self.raw_bytes;
+        let mut serializer = self.reborrow();
+        serializer.raw_bytes = raw_bytes;
+        value.serialize(serializer)
     }
 
     fn end(self) -> CordResult<()> {
@@ -282,7 +778,7 @@ where
     where
         T: ?Sized + Serialize,
     {
-        value.serialize(CordSerializer::new(self.output))
+        value.serialize(self.reborrow())
     }
 
     fn end(self) -> CordResult<()> {
@@ -290,30 +786,127 @@ where
     }
 }
 
-impl<W> ser::SerializeMap for CordSerializer<'_, W>
+// Entries are buffered and emitted sorted by their encoded keys, so that equal maps
+// produce equal bytes regardless of their iteration order.
+struct MapSerializer<'a, W: ?Sized> {
+    serializer: CordSerializer<'a, W>,
+    entries: Vec<(Vec<u8>, Vec<u8>)>,
+    // Set when entries are written as they arrive, in the order of the map
+    streaming: bool,
+}
+
+impl<'a, W> MapSerializer<'a, W>
+where
+    W: ?Sized + std::io::Write,
+{
+    fn new(serializer: CordSerializer<'a, W>, len: usize) -> Self {
+        Self {
+            serializer,
+            entries: Vec::with_capacity(len),
+            streaming: false,
+        }
+    }
+
+    fn streaming(serializer: CordSerializer<'a, W>) -> Self {
+        Self {
+            serializer,
+            entries: Vec::new(),
+            streaming: true,
+        }
+    }
+
+    // Strings within entries bypass the dictionary, as they do when entries are buffered
+    fn entry_serializer(&mut self) -> CordSerializer<'_, W> {
+        CordSerializer {
+            dictionary: None,
+            ..self.serializer.reborrow()
+        }
+    }
+
+    // Entries are reordered after encoding, so strings within them bypass the dictionary
+    fn encode<T>(&self, value: &T) -> CordResult<Vec<u8>>
+    where
+        T: ?Sized + Serialize,
+    {
+        let mut buffer = Vec::new();
+        let options = self.serializer.options;
+        serialize_buffered(&mut buffer, value, options, None, self.serializer.budget)?;
+        Ok(buffer)
+    }
+}
+
+impl<W> ser::SerializeMap for MapSerializer<'_, W>
 where
     W: ?Sized + std::io::Write,
 {
     type Ok = ();
     type Error = CordError;
 
-    fn serialize_key<T>(&mut self, _key: &T) -> CordResult<()>
+    fn serialize_key<T>(&mut self, key: &T) -> CordResult<()>
     where
         T: ?Sized + Serialize,
     {
-        Err(CordError::NotSupported("map key"))
+        if self.streaming {
+            if self.serializer.options.seq_encoding == SeqEncoding::Terminated {
+                self.serializer.write_all(&[1])?;
+            }
+            return key.serialize(self.entry_serializer());
+        }
+        let key = self.encode(key)?;
+        self.entries.push((key, Vec::new()));
+        Ok(())
     }
 
-    fn serialize_value<T>(&mut self, _value: &T) -> CordResult<()>
+    fn serialize_value<T>(&mut self, value: &T) -> CordResult<()>
     where
         T: ?Sized + Serialize,
     {
-        Err(CordError::NotSupported("map value"))
+        if self.streaming {
+            return value.serialize(self.entry_serializer());
+        }
+        let value = self.encode(value)?;
+        match self.entries.last_mut() {
+            Some(entry) => entry.1 = value,
+            None => return Err(ser::Error::custom("map value without a key")),
+        }
+        Ok(())
     }
 
-    #[allow(unused_mut)]
     fn end(mut self) -> CordResult<()> {
-        Err(CordError::NotSupported("map end"))
+        if self.streaming {
+            return match self.serializer.options.seq_encoding {
+                SeqEncoding::LengthPrefixed => Ok(()),
+                SeqEncoding::Terminated => self.serializer.write_all(&[0]),
+                SeqEncoding::Chunked => self.serializer.serialize_usize(0),
+            };
+        }
+        self.entries.sort_by(|a, b| a.0.cmp(&b.0));
+        if self.entries.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+            return Err(CordError::ValidationError("Duplicate map key"));
+        }
+
+        let seq_encoding = self.serializer.options.seq_encoding;
+        let terminated = seq_encoding == SeqEncoding::Terminated;
+        let prefixed = match seq_encoding {
+            SeqEncoding::LengthPrefixed => true,
+            SeqEncoding::Terminated => false,
+            SeqEncoding::Chunked => !self.entries.is_empty(),
+        };
+        if prefixed {
+            self.serializer.serialize_usize(self.entries.len())?;
+        }
+        for (key, value) in &self.entries {
+            if terminated {
+                self.serializer.write_all(&[1])?;
+            }
+            self.serializer.write_buffered(key)?;
+            self.serializer.write_buffered(value)?;
+        }
+        match seq_encoding {
+            SeqEncoding::LengthPrefixed => Ok(()),
+            SeqEncoding::Terminated => self.serializer.write_all(&[0]),
+            SeqEncoding::Chunked => self.serializer.serialize_usize(0),
+        }
     }
 }
 
@@ -324,11 +917,25 @@ where
     type Ok = ();
     type Error = CordError;
 
-    fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> CordResult<()>
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> CordResult<()>
     where
         T: ?Sized + Serialize,
     {
-        value.serialize(CordSerializer::new(self.output))
+        if let Some(remaining) = &mut self.prefix_fields {
+            if *remaining == 0 {
+                return Ok(());
+            }
+            *remaining -= 1;
+        }
+        trace!("serializing field {key} at offset {}", self.offset());
+        value
+            .serialize(self.reborrow())
+            .map_err(|err| err.in_field(key))
+    }
+
+    // Fields are positional, so omitting one would shift every later field
+    fn skip_field(&mut self, key: &'static str) -> CordResult<()> {
+        Err(CordError::NotSupported("skipped field").in_field(key))
     }
 
     fn end(self) -> CordResult<()> {
@@ -343,11 +950,19 @@ where
     type Ok = ();
     type Error = CordError;
 
-    fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> CordResult<()>
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> CordResult<()>
     where
         T: ?Sized + Serialize,
     {
-        value.serialize(CordSerializer::new(self.output))
+        trace!("serializing field {key} at offset {}", self.offset());
+        value
+            .serialize(self.reborrow())
+            .map_err(|err| err.in_field(key))
+    }
+
+    // Fields are positional, so omitting one would shift every later field
+    fn skip_field(&mut self, key: &'static str) -> CordResult<()> {
+        Err(CordError::NotSupported("skipped field").in_field(key))
     }
 
     fn end(self) -> CordResult<()> {
@@ -364,49 +979,268 @@ impl Serialize for crate::Bytes {
     }
 }
 
-impl<T: Serialize + std::clone::Clone + std::cmp::Ord> Serialize for crate::Set<T> {
+// Appends the encoding by which a set orders `element`, shared by encoding and decoding so
+// that both agree on the order
+pub(crate) fn encode_element<T>(scratch: &mut Vec<u8>, element: &T) -> CordResult<()>
+where
+    T: ?Sized + Serialize,
+{
+    serialize_into(scratch, element)
+}
+
+// Sets are written in the order of their elements' encodings, independently of the order
+// in which they are stored. Encoding each element once also surfaces its errors, rather than
+// leaving them to the comparator. The encodings share a single scratch buffer, so that large
+// sets allocate a handful of times rather than once per element.
+pub(crate) fn sort_by_encoding<'t, T, E>(
+    elements: impl ExactSizeIterator<Item = &'t T>,
+) -> Result<Vec<&'t T>, E>
+where
+    T: Serialize + 't,
+    E: ser::Error,
+{
+    let mut scratch = Vec::new();
+    let mut encoded = Vec::with_capacity(elements.len());
+    for element in elements {
+        let start = scratch.len();
+        encode_element(&mut scratch, element).map_err(ser::Error::custom)?;
+        encoded.push((start, scratch.len(), element));
+    }
+    encoded.sort_by(|a, b| scratch[a.0..a.1].cmp(&scratch[b.0..b.1]));
+    Ok(encoded.into_iter().map(|(_, _, element)| element).collect())
+}
+
+impl<const N: usize> Serialize for crate::RawBytes<N> {
     fn serialize<S>(&self, serializer: S) -> CordResult<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let mut vec: Vec<T> = self.into();
-        vec.sort_by_key(|x| serialize(&x).unwrap());
-        vec.serialize(serializer)
+        serializer.serialize_newtype_struct(RAW_BYTES, &ByteTuple(&self.0))
     }
 }
 
-impl ser::Serialize for crate::DateTime {
+// Bytes written one by one as a tuple, the shape in which serde writes `[u8; N]`
+struct ByteTuple<'a>(&'a [u8]);
+
+impl Serialize for ByteTuple<'_> {
     fn serialize<S>(&self, serializer: S) -> CordResult<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        serializer.serialize_u64(self.chrono.timestamp_millis() as u64)
+        let mut tuple = serializer.serialize_tuple(self.0.len())?;
+        for byte in self.0 {
+            tuple.serialize_element(byte)?;
+        }
+        tuple.end()
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::{serialize, DateTime};
-    use crate::{Bytes, CordError};
-    use chrono::Utc;
-    use integer_encoding::VarInt;
-    use serde::Serialize;
-
-    #[test]
-    fn serialize_unit() {
-        assert_eq!(serialize(&()).unwrap(), []);
+impl Serialize for crate::BorrowedBytes<'_> {
+    fn serialize<S>(&self, serializer: S) -> CordResult<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(self.as_slice())
     }
+}
 
-    #[test]
-    fn serialize_booleans() {
-        assert_eq!(serialize(&true).unwrap(), [1]);
-        assert_eq!(serialize(&false).unwrap(), [0]);
+impl<T: Serialize> Serialize for crate::Set<T> {
+    fn serialize<S>(&self, serializer: S) -> CordResult<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_newtype_struct(SET, &sort_by_encoding(self.hashset.iter())?)
     }
+}
 
-    #[test]
-    fn serialize_numbers_as_varints() {
-        let u8: u8 = 62;
-        assert_eq!(serialize(&u8).unwrap(), [62]);
+impl<T: Serialize> Serialize for crate::SortedSet<T> {
+    fn serialize<S>(&self, serializer: S) -> CordResult<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_newtype_struct(SET, &sort_by_encoding(self.btreeset.iter())?)
+    }
+}
+
+impl<T: Serialize> Serialize for crate::PresortedSet<T> {
+    fn serialize<S>(&self, serializer: S) -> CordResult<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        #[cfg(debug_assertions)]
+        {
+            // The two encodings trade places after every element, so that their buffers are
+            // reused
+            let mut previous_element = Vec::new();
+            let mut current_element = Vec::new();
+            for (index, element) in self.0.iter().enumerate() {
+                current_element.clear();
+                encode_element(&mut current_element, element).map_err(ser::Error::custom)?;
+                if index > 0 && previous_element >= current_element {
+                    return Err(ser::Error::custom("presorted set is not sorted"));
+                }
+                std::mem::swap(&mut previous_element, &mut current_element);
+            }
+        }
+        serializer.serialize_newtype_struct(SET, &self.0)
+    }
+}
+
+impl<T: Copy + Into<u64>> Serialize for crate::DeltaSeq<T> {
+    fn serialize<S>(&self, serializer: S) -> CordResult<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+        let mut previous = 0_u64;
+        for element in &self.0 {
+            let current: u64 = (*element).into();
+            let delta = current
+                .checked_sub(previous)
+                .ok_or_else(|| ser::Error::custom("delta sequence is not sorted"))?;
+            seq.serialize_element(&delta)?;
+            previous = current;
+        }
+        seq.end()
+    }
+}
+
+impl ser::Serialize for crate::DateTime {
+    fn serialize<S>(&self, serializer: S) -> CordResult<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u64(self.chrono.timestamp_millis() as u64)
+    }
+}
+
+impl ser::Serialize for crate::DateTimeWithOffset {
+    fn serialize<S>(&self, serializer: S) -> CordResult<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let offset_seconds = self.chrono.offset().local_minus_utc();
+        if offset_seconds % 60 != 0 {
+            return Err(ser::Error::custom(
+                "offset is not a whole number of minutes",
+            ));
+        }
+
+        let mut state = serializer.serialize_struct("DateTimeWithOffset", 2)?;
+        state.serialize_field("instant", &crate::DateTime::from(self))?;
+        state.serialize_field("offset", &(offset_seconds / 60))?;
+        state.end()
+    }
+}
+
+impl ser::Serialize for crate::Date {
+    fn serialize<S>(&self, serializer: S) -> CordResult<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // The default date is the Unix epoch
+        let days = self
+            .chrono
+            .signed_duration_since(chrono::NaiveDate::default());
+        serializer.serialize_i32(days.num_days() as i32)
+    }
+}
+
+impl ser::Serialize for crate::Time {
+    fn serialize<S>(&self, serializer: S) -> CordResult<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use chrono::Timelike;
+
+        let millis = self.chrono.nanosecond() / 1_000_000;
+        if millis >= 1_000 {
+            return Err(ser::Error::custom("leap seconds are not supported"));
+        }
+        serializer.serialize_u32(self.chrono.num_seconds_from_midnight() * 1_000 + millis)
+    }
+}
+
+impl ser::Serialize for crate::NaiveDateTime {
+    fn serialize<S>(&self, serializer: S) -> CordResult<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        crate::DateTime::from(self.chrono.and_utc()).serialize(serializer)
+    }
+}
+
+impl<P: crate::Precision> ser::Serialize for crate::Timestamp<P> {
+    fn serialize<S>(&self, serializer: S) -> CordResult<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let units = P::to_units(&self.chrono)
+            .ok_or_else(|| ser::Error::custom("timestamp out of range for precision"))?;
+        serializer.serialize_u64(units as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        hash_into, length_prefix_len, serialize, serialize_into, serialize_into_counted,
+        serialize_into_slice, serialize_prefix, serialize_slice, serialize_versioned,
+        serialize_with_options, signed_varint_len, varint_len, Date, DateTime, DateTimeWithOffset,
+        Encoder, IntEncoding, Millis, NaiveDateTime, Nanos, Options, Seconds, Time, Timestamp,
+        FORMAT_VERSION,
+    };
+    use crate::{Bytes, CordError, SeqEncoding, StrEncoding, StructEncoding};
+    use chrono::Utc;
+    use integer_encoding::VarInt;
+    use serde::Serialize;
+    use std::borrow::Cow;
+    use std::collections::{BTreeMap, HashMap};
+
+    #[test]
+    fn serialize_versioned_prefixes_version() {
+        assert_eq!(FORMAT_VERSION, 1);
+        assert_eq!(
+            serialize_versioned("test").unwrap(),
+            [1, 4, 116, 101, 115, 116]
+        );
+    }
+
+    #[test]
+    fn hash_matches_serialized_bytes() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::Hasher;
+
+        let value = (300_u16, "test", vec![1_u8, 2, 3]);
+        let mut streamed = DefaultHasher::new();
+        hash_into(&mut streamed, &value).unwrap();
+
+        let mut buffered = DefaultHasher::new();
+        buffered.write(&serialize(&value).unwrap());
+        assert_eq!(streamed.finish(), buffered.finish());
+
+        let mut hasher = DefaultHasher::new();
+        assert_eq!(
+            hash_into(&mut hasher, &1.5_f64).unwrap_err(),
+            CordError::NotSupported("f64")
+        );
+    }
+
+    #[test]
+    fn serialize_unit() {
+        assert!(serialize(&()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn serialize_booleans() {
+        assert_eq!(serialize(&true).unwrap(), [1]);
+        assert_eq!(serialize(&false).unwrap(), [0]);
+    }
+
+    #[test]
+    fn serialize_numbers_as_varints() {
+        let u8: u8 = 62;
+        assert_eq!(serialize(&u8).unwrap(), [62]);
         assert_eq!(serialize(&u8).unwrap(), u8.encode_var_vec().as_slice());
 
         let i8: i8 = -30;
@@ -424,14 +1258,519 @@ mod tests {
         let u32_xs: u32 = 12;
         assert_eq!(serialize(&u32_xs).unwrap(), [12]);
         assert_eq!(
-            serialize(&u32_xs).unwrap(),
-            u32_xs.encode_var_vec().as_slice()
+            serialize(&u32_xs).unwrap(),
+            u32_xs.encode_var_vec().as_slice()
+        );
+    }
+
+    #[test]
+    fn serialize_numbers_as_fixed_big_endian() {
+        let options = Options::new().int_encoding(IntEncoding::FixedBigEndian);
+
+        assert_eq!(serialize_with_options(&62_u8, options).unwrap(), [62]);
+        assert_eq!(serialize_with_options(&300_u16, options).unwrap(), [1, 44]);
+        assert_eq!(serialize_with_options(&-1_i8, options).unwrap(), [0x7f]);
+        assert_eq!(
+            serialize_with_options(&-2_i32, options).unwrap(),
+            [0x7f, 0xff, 0xff, 0xfe]
+        );
+        assert_eq!(
+            serialize_with_options(&1_u64, options).unwrap(),
+            [0, 0, 0, 0, 0, 0, 0, 1]
+        );
+    }
+
+    #[test]
+    fn serialize_fixed_big_endian_independent_of_host() {
+        let options = Options::new().int_encoding(IntEncoding::FixedBigEndian);
+
+        assert_eq!(
+            serialize_with_options(&0x0102_u16, options).unwrap(),
+            0x0102_u16.to_be_bytes()
+        );
+        assert_eq!(
+            serialize_with_options(&0x0102_0304_u32, options).unwrap(),
+            0x0102_0304_u32.to_be_bytes()
+        );
+        assert_eq!(
+            serialize_with_options(&0x0102_0304_0506_0708_u64, options).unwrap(),
+            0x0102_0304_0506_0708_u64.to_be_bytes()
+        );
+        assert_eq!(
+            serialize_with_options(&-0x0102_i16, options).unwrap(),
+            ((-0x0102_i16 as u16) ^ 0x8000).to_be_bytes()
+        );
+        assert_eq!(
+            serialize_with_options(&-0x0102_0304_i32, options).unwrap(),
+            ((-0x0102_0304_i32 as u32) ^ 0x8000_0000).to_be_bytes()
+        );
+        assert_eq!(
+            serialize_with_options(&-0x0102_0304_0506_0708_i64, options).unwrap(),
+            ((-0x0102_0304_0506_0708_i64 as u64) ^ 0x8000_0000_0000_0000).to_be_bytes()
+        );
+    }
+
+    #[test]
+    fn serialize_fixed_big_endian_preserves_order() {
+        let options = Options::new().int_encoding(IntEncoding::FixedBigEndian);
+        let values: Vec<i64> = vec![i64::MIN, -300, -1, 0, 1, 127, 128, 300, i64::MAX];
+        let encoded: Vec<Vec<u8>> = values
+            .iter()
+            .map(|v| serialize_with_options(v, options).unwrap())
+            .collect();
+
+        assert!(encoded.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn serialize_128_bit_integers() {
+        assert_eq!(serialize(&300_u128).unwrap(), serialize(&300_u64).unwrap());
+        assert_eq!(
+            serialize(&-300_i128).unwrap(),
+            serialize(&-300_i64).unwrap()
+        );
+
+        let mut expected = vec![0xff; 18];
+        expected.push(0x03);
+        assert_eq!(serialize(&u128::MAX).unwrap(), expected);
+        assert_eq!(serialize(&i128::MIN).unwrap(), expected);
+
+        let options = Options::new().int_encoding(IntEncoding::FixedBigEndian);
+        assert_eq!(
+            serialize_with_options(&1_u128, options).unwrap(),
+            1_u128.to_be_bytes()
+        );
+        assert_eq!(
+            serialize_with_options(&-1_i128, options).unwrap(),
+            i128::MAX.to_be_bytes()
+        );
+    }
+
+    #[test]
+    fn varint_lengths() {
+        for value in [0_u64, 127, 128, 300, 16_383, 16_384, u64::MAX] {
+            assert_eq!(varint_len(value), serialize(&value).unwrap().len());
+        }
+        assert_eq!(varint_len(u64::MAX), 10);
+
+        for value in [0_i64, -1, 63, -64, 64, -65, i64::MIN, i64::MAX] {
+            assert_eq!(signed_varint_len(value), serialize(&value).unwrap().len());
+        }
+        assert_eq!(signed_varint_len(-65), 2);
+
+        assert_eq!(length_prefix_len(4), 1);
+        assert_eq!(length_prefix_len(300), 2);
+        let value = vec![0_u8; 300];
+        assert_eq!(
+            serialize(&value).unwrap().len(),
+            length_prefix_len(300) + 300
+        );
+    }
+
+    #[test]
+    fn serialize_with_encoder() {
+        let mut buffer = vec![9];
+        Encoder::new(&mut buffer)
+            .value("test")
+            .unwrap()
+            .value(&300_u16)
+            .unwrap();
+        assert_eq!(buffer, [9, 4, 116, 101, 115, 116, 172, 2]);
+
+        let mut encoder = Encoder::with_options(&mut buffer, Options::order_preserving());
+        encoder.value(&7_u16).unwrap();
+        assert_eq!(
+            encoder.value(&(1_u8, 'x')).unwrap_err(),
+            CordError::NotSupported("char")
+        );
+        assert_eq!(buffer, [9, 4, 116, 101, 115, 116, 172, 2, 0, 7]);
+    }
+
+    #[test]
+    fn serialize_strings() {
+        assert_eq!(serialize("test").unwrap(), [4, 116, 101, 115, 116]);
+    }
+
+    #[test]
+    fn serialize_str_and_string_identically() {
+        let string = String::from("test");
+        for options in [
+            Options::new(),
+            Options::order_preserving(),
+            Options::new().str_encoding(StrEncoding::NulTerminated),
+            Options::new().string_dictionary(),
+        ] {
+            let expected = serialize_with_options("test", options).unwrap();
+            assert_eq!(serialize_with_options(&string, options).unwrap(), expected);
+            assert_eq!(serialize_with_options(&&string, options).unwrap(), expected);
+            assert_eq!(
+                serialize_with_options(&Box::<str>::from("test"), options).unwrap(),
+                expected
+            );
+            assert_eq!(
+                serialize_with_options(&std::borrow::Cow::Borrowed("test"), options).unwrap(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn serialize_cow_variants_identically() {
+        for options in [Options::new(), Options::order_preserving()] {
+            let borrowed: Cow<str> = Cow::Borrowed("test");
+            let owned: Cow<str> = Cow::Owned(String::from("test"));
+            assert_eq!(
+                serialize_with_options(&borrowed, options).unwrap(),
+                serialize_with_options(&owned, options).unwrap()
+            );
+
+            let borrowed: Cow<[u8]> = Cow::Borrowed(&[0, 1, 2]);
+            let owned: Cow<[u8]> = Cow::Owned(vec![0, 1, 2]);
+            let expected = serialize_with_options(&vec![0_u8, 1, 2], options).unwrap();
+            assert_eq!(
+                serialize_with_options(&borrowed, options).unwrap(),
+                expected
+            );
+            assert_eq!(serialize_with_options(&owned, options).unwrap(), expected);
+        }
+
+        let owned: Cow<str> = Cow::Owned(String::from("test"));
+        assert_eq!(serialize(&owned).unwrap(), [4, 116, 101, 115, 116]);
+        let owned: Cow<[u8]> = Cow::Owned(vec![0, 1, 2]);
+        assert_eq!(serialize(&owned).unwrap(), [3, 0, 1, 2]);
+    }
+
+    #[derive(Serialize)]
+    struct Key<'a> {
+        tenant: u16,
+        path: &'a str,
+        versions: Vec<i32>,
+    }
+
+    #[test]
+    fn serialize_order_preserving() {
+        let key = Key {
+            tenant: 7,
+            path: "a\0b",
+            versions: vec![-1, 2],
+        };
+
+        assert_eq!(
+            serialize_with_options(&key, Options::order_preserving()).unwrap(),
+            vec![
+                0, 7, // Serialize `tenant`
+                97, 0, 0xff, 98, 0, 1, // Serialize `path`
+                1, 0x7f, 0xff, 0xff, 0xff, // Serialize versions[0]
+                1, 0x80, 0, 0, 2, // Serialize versions[1]
+                0, // Terminate `versions`
+            ]
+        );
+    }
+
+    #[test]
+    fn serialize_order_preserving_matches_logical_order() {
+        let keys = [
+            Key {
+                tenant: 1,
+                path: "",
+                versions: vec![],
+            },
+            Key {
+                tenant: 1,
+                path: "",
+                versions: vec![-5],
+            },
+            Key {
+                tenant: 1,
+                path: "",
+                versions: vec![3, 1],
+            },
+            Key {
+                tenant: 1,
+                path: "\0",
+                versions: vec![],
+            },
+            Key {
+                tenant: 1,
+                path: "a",
+                versions: vec![],
+            },
+            Key {
+                tenant: 1,
+                path: "a\0",
+                versions: vec![],
+            },
+            Key {
+                tenant: 1,
+                path: "aa",
+                versions: vec![],
+            },
+            Key {
+                tenant: 1,
+                path: "z",
+                versions: vec![],
+            },
+            Key {
+                tenant: 300,
+                path: "",
+                versions: vec![],
+            },
+        ];
+        let encoded: Vec<Vec<u8>> = keys
+            .iter()
+            .map(|key| serialize_with_options(key, Options::order_preserving()).unwrap())
+            .collect();
+
+        assert!(encoded.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn serialize_into_writer() {
+        let mut output = vec![9];
+        serialize_into(&mut output, "test").unwrap();
+        assert_eq!(output, [9, 4, 116, 101, 115, 116]);
+    }
+
+    #[test]
+    fn serialize_into_writer_counted() {
+        let mut output = vec![9];
+        assert_eq!(serialize_into_counted(&mut output, "test").unwrap(), 5);
+        assert_eq!(serialize_into_counted(&mut output, &300_u16).unwrap(), 2);
+        assert_eq!(output, [9, 4, 116, 101, 115, 116, 172, 2]);
+    }
+
+    #[test]
+    fn serialize_into_stack_buffer() {
+        let mut buffer = [0_u8; 8];
+        let written = serialize_into_slice("test", &mut buffer).unwrap();
+
+        assert_eq!(written, 5);
+        assert_eq!(buffer[..written], [4, 116, 101, 115, 116]);
+    }
+
+    #[test]
+    fn serialize_into_undersized_buffer() {
+        let mut buffer = [0_u8; 4];
+        assert_eq!(
+            serialize_into_slice("test", &mut buffer).unwrap_err(),
+            CordError::BufferFull {
+                needed: 5,
+                capacity: 4
+            }
+        );
+    }
+
+    #[test]
+    fn serialize_nul_terminated_strings() {
+        let options = Options::new().str_encoding(StrEncoding::NulTerminated);
+
+        assert_eq!(
+            serialize_with_options("test", options).unwrap(),
+            [116, 101, 115, 116, 0]
+        );
+        assert_eq!(serialize_with_options("", options).unwrap(), [0]);
+        assert_eq!(
+            serialize_with_options("te\0st", options).unwrap_err(),
+            CordError::ValidationError("Unexpected nul byte")
+        );
+    }
+
+    struct Evens<'a>(&'a [u16]);
+
+    impl Serialize for Evens<'_> {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.0.iter().filter(|v| *v % 2 == 0))
+        }
+    }
+
+    #[test]
+    fn serialize_unsized_sequences() {
+        let value = Evens(&[1, 2, 3, 300, 5]);
+        assert_eq!(
+            serialize(&value).unwrap_err(),
+            CordError::NotSupported("unsized sequences")
+        );
+
+        let options = Options::new().allow_unsized_seq();
+        assert_eq!(
+            serialize_with_options(&value, options).unwrap(),
+            [2, 2, 172, 2]
+        );
+        assert_eq!(
+            serialize_with_options(&value, options).unwrap(),
+            serialize(&vec![2_u16, 300]).unwrap()
+        );
+        assert_eq!(serialize_with_options(&Evens(&[]), options).unwrap(), [0]);
+
+        let options = Options::order_preserving().allow_unsized_seq();
+        assert_eq!(
+            serialize_with_options(&value, options).unwrap(),
+            [1, 0, 2, 1, 1, 44, 0]
+        );
+    }
+
+    #[test]
+    fn serialize_chunked_sequences() {
+        let options = Options::new().streaming_seq();
+        assert_eq!(
+            serialize_with_options(&Evens(&[1, 2, 3, 300, 5]), options).unwrap(),
+            [2, 2, 172, 2, 0]
+        );
+        assert_eq!(serialize_with_options(&Evens(&[]), options).unwrap(), [0]);
+        assert_eq!(
+            serialize_with_options(&vec![1_u8, 2], options).unwrap(),
+            [2, 1, 2, 0]
+        );
+        assert_eq!(
+            serialize_with_options(&Vec::<u8>::new(), options).unwrap(),
+            [0]
+        );
+
+        let long: Vec<u16> = vec![2; 300];
+        let mut expected = vec![128, 1];
+        expected.extend_from_slice(&[2; 128]);
+        expected.extend_from_slice(&[128, 1]);
+        expected.extend_from_slice(&[2; 128]);
+        expected.push(44);
+        expected.extend_from_slice(&[2; 44]);
+        expected.push(0);
+        assert_eq!(
+            serialize_with_options(&Evens(&long), options).unwrap(),
+            expected
+        );
+
+        let mut map = BTreeMap::new();
+        assert_eq!(serialize_with_options(&map, options).unwrap(), [0]);
+        map.insert(2_u8, 3_u8);
+        map.insert(1_u8, 4_u8);
+        assert_eq!(
+            serialize_with_options(&map, options).unwrap(),
+            [2, 1, 4, 2, 3, 0]
+        );
+    }
+
+    #[test]
+    fn serialize_output_limit() {
+        let value = vec![7_u8; 10];
+        let options = Options::new().max_output_len(11);
+        assert_eq!(serialize_with_options(&value, options).unwrap().len(), 11);
+
+        let options = Options::new().max_output_len(10);
+        assert_eq!(
+            serialize_with_options(&value, options).unwrap_err(),
+            CordError::OutputLimitExceeded { limit: 10 }
+        );
+        assert_eq!(
+            serialize_with_options(&vec![value.clone(); 1000], options).unwrap_err(),
+            CordError::OutputLimitExceeded { limit: 10 }
+        );
+
+        let map: BTreeMap<u8, Vec<u8>> = vec![(1, value)].into_iter().collect();
+        assert_eq!(
+            serialize_with_options(&map, options).unwrap_err(),
+            CordError::OutputLimitExceeded { limit: 10 }
+        );
+
+        let mut buffer = vec![1];
+        let mut encoder = Encoder::with_options(&mut buffer, Options::new().max_output_len(2));
+        encoder.value(&300_u16).unwrap();
+        assert!(encoder.value("test").is_err());
+        assert_eq!(buffer, [1, 172, 2]);
+    }
+
+    // Emits `len` entries or elements of unknown count, recording how many were produced
+    struct Endless<'a> {
+        len: u32,
+        produced: &'a std::cell::Cell<u32>,
+        map: bool,
+    }
+
+    impl Serialize for Endless<'_> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            let items = (0..self.len)
+                .inspect(|_| self.produced.set(self.produced.get() + 1))
+                .filter(|_| true);
+            if self.map {
+                serializer.collect_map(items.map(|item| (item, item)))
+            } else {
+                serializer.collect_seq(items)
+            }
+        }
+    }
+
+    #[test]
+    fn serialize_output_limit_oversized_map() {
+        let produced = std::cell::Cell::new(0);
+        let map = Endless {
+            len: 1_000_000,
+            produced: &produced,
+            map: true,
+        };
+        let options = Options::new().max_output_len(10);
+        assert_eq!(
+            serialize_with_options(&map, options).unwrap_err(),
+            CordError::OutputLimitExceeded { limit: 10 }
+        );
+        assert!(produced.get() < 10);
+    }
+
+    #[test]
+    fn serialize_output_limit_unsized_seq() {
+        let produced = std::cell::Cell::new(0);
+        let seq = Endless {
+            len: 1_000_000,
+            produced: &produced,
+            map: false,
+        };
+        let options = Options::new().allow_unsized_seq().max_output_len(10);
+        assert_eq!(
+            serialize_with_options(&seq, options).unwrap_err(),
+            CordError::OutputLimitExceeded { limit: 10 }
+        );
+        assert!(produced.get() < 20);
+
+        produced.set(0);
+        let options = Options::new().streaming_seq().max_output_len(10);
+        assert_eq!(
+            serialize_with_options(&seq, options).unwrap_err(),
+            CordError::OutputLimitExceeded { limit: 10 }
         );
+        assert!(produced.get() < 20);
+
+        let seq = Endless {
+            len: 9,
+            produced: &produced,
+            map: false,
+        };
+        let options = Options::new().allow_unsized_seq().max_output_len(10);
+        assert_eq!(serialize_with_options(&seq, options).unwrap().len(), 10);
+    }
+
+    #[derive(Serialize)]
+    struct Labelled {
+        label: &'static str,
+        labels: BTreeMap<&'static str, &'static str>,
     }
 
     #[test]
-    fn serialize_strings() {
-        assert_eq!(serialize("test").unwrap(), [4, 116, 101, 115, 116]);
+    fn serialize_string_dictionary() {
+        let options = Options::new().string_dictionary();
+        assert_eq!(
+            serialize_with_options(&vec!["ab", "cd", "ab", "ab"], options).unwrap(),
+            [4, 0, 2, 97, 98, 0, 2, 99, 100, 1, 1]
+        );
+
+        let value = Labelled {
+            label: "ab",
+            labels: BTreeMap::from([("ab", "ab")]),
+        };
+        assert_eq!(
+            serialize_with_options(&value, options).unwrap(),
+            [0, 2, 97, 98, 1, 2, 97, 98, 2, 97, 98]
+        );
     }
 
     #[test]
@@ -462,18 +1801,82 @@ mod tests {
         assert_eq!(serialize(&bytes).unwrap(), vec![3, 0, 1, 2]);
     }
 
+    #[derive(Serialize)]
+    struct Header {
+        magic: crate::RawBytes<4>,
+        version: u16,
+    }
+
+    #[test]
+    fn serialize_fixed_length_bytes() {
+        let header = Header {
+            magic: crate::RawBytes(*b"CORD"),
+            version: 300,
+        };
+        assert_eq!(serialize(&header).unwrap(), [67, 79, 82, 68, 172, 2]);
+        assert_eq!(
+            serialize(&crate::RawBytes([0, 200, 1])).unwrap(),
+            [0, 200, 1]
+        );
+        assert_eq!(
+            serialize_with_options(&crate::RawBytes([0, 200, 1]), Options::order_preserving())
+                .unwrap(),
+            [0, 200, 1]
+        );
+        assert!(serialize(&crate::RawBytes([])).unwrap().is_empty());
+    }
+
     #[test]
     fn serialize_raw_bytes() {
         let bytes: Vec<u8> = vec![0, 1, 2];
         assert_eq!(serialize(&bytes).unwrap(), [3, 0, 1, 2]);
     }
 
+    #[test]
+    fn serialize_slice_matches_vec() {
+        let items = vec![String::from("first"), String::from("second")];
+
+        assert_eq!(
+            serialize_slice(&items[..]).unwrap(),
+            serialize(&items).unwrap()
+        );
+        assert_eq!(
+            crate::deserialize::<Vec<String>>(&serialize_slice(&items[..]).unwrap()).unwrap(),
+            items
+        );
+        assert_eq!(serialize_slice::<u8>(&[]).unwrap(), [0]);
+    }
+
     #[test]
     fn serialize_tuple() {
         let bytes: [u8; 3] = [0, 1, 2];
         assert_eq!(serialize(&bytes).unwrap(), vec![3, 0, 1, 2]);
     }
 
+    #[derive(Serialize)]
+    struct LargeArrays {
+        #[serde(with = "crate::array")]
+        bytes: [u8; 64],
+        #[serde(with = "crate::array")]
+        words: [u16; 128],
+    }
+
+    #[test]
+    fn serialize_large_arrays() {
+        let mut bytes = [0_u8; 64];
+        bytes[63] = 63;
+        let value = LargeArrays {
+            bytes,
+            words: [1; 128],
+        };
+
+        let mut expected: Vec<u8> = vec![64];
+        expected.extend_from_slice(&bytes);
+        expected.extend_from_slice(&[128, 1]);
+        expected.extend(vec![1; 128]);
+        assert_eq!(serialize(&value).unwrap(), expected);
+    }
+
     #[test]
     fn serialize_datetime() {
         let datetime: DateTime = chrono::DateTime::parse_from_rfc3339("2023-10-05T14:30:00.000Z")
@@ -487,6 +1890,74 @@ mod tests {
         );
     }
 
+    #[test]
+    fn serialize_date() {
+        let date = Date::from(chrono::NaiveDate::from_ymd_opt(2023, 10, 5).unwrap());
+        assert_eq!(serialize(&date).unwrap(), [230, 178, 2]);
+
+        let date = Date::from(chrono::NaiveDate::from_ymd_opt(1969, 12, 31).unwrap());
+        assert_eq!(serialize(&date).unwrap(), [1]);
+    }
+
+    #[test]
+    fn serialize_time() {
+        let time = Time::from(chrono::NaiveTime::from_hms_milli_opt(14, 30, 0, 250).unwrap());
+        assert_eq!(serialize(&time).unwrap(), [186, 134, 242, 24]);
+
+        let leap = Time::from(chrono::NaiveTime::from_hms_milli_opt(23, 59, 59, 1_500).unwrap());
+        assert_eq!(
+            serialize(&leap).unwrap_err(),
+            CordError::SerializationError(String::from("leap seconds are not supported"))
+        );
+    }
+
+    #[test]
+    fn serialize_naive_datetime() {
+        let datetime = NaiveDateTime::from(
+            chrono::NaiveDate::from_ymd_opt(2023, 10, 5)
+                .unwrap()
+                .and_hms_opt(14, 30, 0)
+                .unwrap(),
+        );
+        assert_eq!(
+            serialize(&datetime).unwrap(),
+            vec![192, 172, 251, 129, 176, 49]
+        );
+    }
+
+    #[test]
+    fn serialize_timestamp_precisions() {
+        let chrono = chrono::DateTime::parse_from_rfc3339("2023-10-05T14:30:00.123456789Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(
+            serialize(&Timestamp::<Seconds>::from(chrono)).unwrap(),
+            vec![232, 144, 251, 168, 6]
+        );
+        assert_eq!(
+            serialize(&Timestamp::<Millis>::from(chrono)).unwrap(),
+            serialize(&DateTime::from(chrono)).unwrap()
+        );
+        assert_eq!(
+            serialize(&Timestamp::<Nanos>::from(chrono)).unwrap(),
+            (chrono.timestamp_nanos_opt().unwrap() as u64).encode_var_vec()
+        );
+    }
+
+    #[test]
+    fn serialize_datetime_with_offset() {
+        let datetime: DateTimeWithOffset =
+            chrono::DateTime::parse_from_rfc3339("2023-10-05T16:30:00.000+02:00")
+                .unwrap()
+                .into();
+
+        assert_eq!(
+            serialize(&datetime).unwrap(),
+            vec![192, 172, 251, 129, 176, 49, 240, 1]
+        );
+    }
+
     #[test]
     fn serialize_set() {
         let set: crate::Set<String> = vec!["a", "b", "c", "d", "e", "f", "test"]
@@ -500,6 +1971,144 @@ mod tests {
         );
     }
 
+    #[test]
+    fn serialize_set_orders_by_encoded_bytes() {
+        let set: crate::Set<String> = vec!["aa", "z", "b"]
+            .into_iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        assert_eq!(serialize(&set).unwrap(), [3, 1, 98, 1, 122, 2, 97, 97]);
+    }
+
+    #[test]
+    fn serialize_set_independently_of_insertion_order() {
+        let orders = [
+            vec!["aa", "z", "b", "", "test"],
+            vec!["test", "", "b", "z", "aa"],
+            vec!["b", "test", "aa", "", "z"],
+        ];
+        let expected: Vec<u8> = vec![5, 0, 1, 98, 1, 122, 2, 97, 97, 4, 116, 101, 115, 116];
+        for order in orders {
+            let mut hashset = std::collections::HashSet::new();
+            for element in order {
+                hashset.insert(element.to_string());
+            }
+            assert_eq!(serialize(&crate::Set::from(hashset)).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn serialize_large_set() {
+        let set: crate::Set<u16> = (0..300).collect();
+        let mut elements: Vec<Vec<u8>> = (0_u16..300).map(|v| serialize(&v).unwrap()).collect();
+        elements.sort();
+
+        let mut expected: Vec<u8> = vec![172, 2];
+        expected.extend(elements.concat());
+        assert_eq!(serialize(&set).unwrap(), expected);
+    }
+
+    #[cfg(feature = "smallvec")]
+    #[test]
+    fn serialize_small() {
+        let inline = super::serialize_small::<4, _>(&(1_u8, "a")).unwrap();
+        assert_eq!(inline.as_slice(), [2, 1, 1, 97]);
+        assert!(!inline.spilled());
+
+        let spilled = super::serialize_small::<4, _>(&(1_u8, "ab")).unwrap();
+        assert_eq!(spilled.as_slice(), [2, 1, 2, 97, 98]);
+        assert!(spilled.spilled());
+    }
+
+    // `Saturating` encodes the same way, but is newer than the minimum supported Rust version
+    #[test]
+    fn serialize_integer_wrappers() {
+        use std::num::Wrapping;
+
+        let bytes = serialize(&Wrapping(300_u32)).unwrap();
+        assert_eq!(bytes, [172, 2]);
+        assert_eq!(
+            crate::deserialize::<Wrapping<u32>>(&bytes).unwrap(),
+            Wrapping(300)
+        );
+
+        let bytes = serialize(&Wrapping(u64::MAX)).unwrap();
+        assert_eq!(bytes, serialize(&u64::MAX).unwrap());
+        assert_eq!(
+            crate::deserialize::<Wrapping<u64>>(&bytes).unwrap(),
+            Wrapping(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn serialize_bound() {
+        use std::ops::Bound;
+
+        let cases = [
+            (Bound::Unbounded, vec![0]),
+            (Bound::Included(300_u64), vec![1, 172, 2]),
+            (Bound::Excluded(7_u64), vec![2, 7]),
+        ];
+        for (bound, expected) in cases {
+            let bytes = serialize(&bound).unwrap();
+            assert_eq!(bytes, expected);
+            assert_eq!(crate::deserialize::<Bound<u64>>(&bytes).unwrap(), bound);
+        }
+        assert!(crate::deserialize::<Bound<u64>>(&[3, 7]).is_err());
+    }
+
+    #[test]
+    fn serialize_set_element_errors() {
+        let set: crate::Set<Vec<u8>> = crate::Set::from(vec![vec![], vec![1]]);
+        assert!(serialize(&set).is_ok());
+
+        let set: crate::Set<char> = crate::Set::from(vec!['a', 'b']);
+        assert_eq!(
+            serialize(&set).unwrap_err(),
+            CordError::SerializationError(String::from("Cord does not support: char"))
+        );
+    }
+
+    #[test]
+    fn serialize_sorted_set_matches_set() {
+        let values = ["aa", "z", "b"];
+        let set: crate::Set<&str> = values.iter().copied().collect();
+        let sorted: crate::SortedSet<&str> = values.iter().copied().collect();
+
+        assert_eq!(serialize(&sorted).unwrap(), [3, 1, 98, 1, 122, 2, 97, 97]);
+        assert_eq!(serialize(&sorted).unwrap(), serialize(&set).unwrap());
+    }
+
+    #[test]
+    fn serialize_presorted_set_matches_set() {
+        let set: crate::Set<&str> = ["aa", "z", "b"].iter().copied().collect();
+        let presorted = crate::PresortedSet(vec!["b", "z", "aa"]);
+        assert_eq!(serialize(&presorted).unwrap(), serialize(&set).unwrap());
+
+        if cfg!(debug_assertions) {
+            let unsorted = crate::PresortedSet(vec!["aa", "b"]);
+            assert_eq!(
+                serialize(&unsorted).unwrap_err(),
+                CordError::SerializationError(String::from("presorted set is not sorted"))
+            );
+            let duplicated = crate::PresortedSet(vec!["b", "b"]);
+            assert!(serialize(&duplicated).is_err());
+        }
+    }
+
+    #[test]
+    fn serialize_delta_seq() {
+        let seq = crate::DeltaSeq(vec![1000_u32, 1001, 1001, 1003]);
+        assert_eq!(serialize(&seq).unwrap(), [4, 232, 7, 1, 0, 2]);
+
+        let seq = crate::DeltaSeq(vec![2_u8, 1]);
+        assert_eq!(
+            serialize(&seq).unwrap_err(),
+            CordError::SerializationError(String::from("delta sequence is not sorted"))
+        );
+    }
+
     #[derive(Debug, Serialize, PartialEq)]
     enum Enum {
         Unit,
@@ -519,6 +2128,210 @@ mod tests {
         assert_eq!(serialize(&Enum::Struct { field: 1 }).unwrap(), vec![3, 1]);
     }
 
+    #[derive(Serialize)]
+    enum Empty {
+        Tuple(),
+        Struct {},
+        Value(u8),
+    }
+
+    #[test]
+    fn serialize_zero_field_variants() {
+        assert_eq!(serialize(&Empty::Tuple()).unwrap(), [0]);
+        assert_eq!(serialize(&Empty::Struct {}).unwrap(), [1]);
+        assert_eq!(serialize(&Empty::Value(7)).unwrap(), [2, 7]);
+
+        let options = Options::new().struct_encoding(StructEncoding::LengthPrefixed);
+        assert_eq!(
+            serialize_with_options(&Empty::Tuple(), options).unwrap(),
+            [0]
+        );
+        assert_eq!(
+            serialize_with_options(&Empty::Struct {}, options).unwrap(),
+            [1, 0]
+        );
+    }
+
+    #[derive(Serialize)]
+    struct Entry {
+        tenant: u16,
+        path: &'static str,
+        owner: Struct,
+    }
+
+    #[test]
+    fn serialize_struct_prefix() {
+        let entry = Entry {
+            tenant: 300,
+            path: "a",
+            owner: Struct {
+                int: 1,
+                option: None,
+                seq: vec![],
+                boolean: true,
+            },
+        };
+        let full = serialize(&entry).unwrap();
+        assert_eq!(full, [172, 2, 1, 97, 1, 0, 0, 1]);
+        assert!(serialize_prefix(&entry, 0).unwrap().is_empty());
+        assert_eq!(serialize_prefix(&entry, 1).unwrap(), [172, 2]);
+        assert_eq!(serialize_prefix(&entry, 2).unwrap(), [172, 2, 1, 97]);
+        assert_eq!(serialize_prefix(&entry, 3).unwrap(), full);
+        assert_eq!(serialize_prefix(&entry, 4).unwrap(), full);
+        assert_eq!(serialize_prefix(&vec![entry], 1).unwrap().len(), 9);
+    }
+
+    #[derive(Serialize)]
+    enum Record {
+        Entry(Entry),
+        Pair { first: u8, second: u8 },
+    }
+
+    #[test]
+    fn serialize_prefix_in_full() {
+        let entry = || Entry {
+            tenant: 300,
+            path: "a",
+            owner: Struct {
+                int: 1,
+                option: None,
+                seq: vec![],
+                boolean: true,
+            },
+        };
+        let option = Some(entry());
+        assert_eq!(
+            serialize_prefix(&option, 1).unwrap(),
+            serialize(&option).unwrap()
+        );
+        let variant = Record::Entry(entry());
+        assert_eq!(
+            serialize_prefix(&variant, 1).unwrap(),
+            serialize(&variant).unwrap()
+        );
+        let variant = Record::Pair {
+            first: 1,
+            second: 2,
+        };
+        assert_eq!(serialize_prefix(&variant, 1).unwrap(), [1, 1, 2]);
+    }
+
+    #[test]
+    fn serialize_fixed_variant_index() {
+        let options = Options::new().fixed_variant_index();
+        assert_eq!(
+            serialize_with_options(&Enum::Unit, options).unwrap(),
+            [0, 0, 0, 0]
+        );
+        assert_eq!(
+            serialize_with_options(&Enum::Struct { field: 1 }, options).unwrap(),
+            [3, 0, 0, 0, 1]
+        );
+        assert_eq!(
+            serialize_with_options(&UnitVariant(300), options).unwrap(),
+            [44, 1, 0, 0]
+        );
+
+        let options = Options::order_preserving().fixed_variant_index();
+        assert_eq!(
+            serialize_with_options(&Enum::Container(1), options).unwrap(),
+            [1, 0, 0, 0, 0, 1]
+        );
+    }
+
+    #[test]
+    fn serialize_nested_option() {
+        assert_eq!(serialize::<Option<Option<u32>>>(&None).unwrap(), vec![0]);
+        assert_eq!(serialize(&Some(None::<u32>)).unwrap(), vec![1, 0]);
+        assert_eq!(serialize(&Some(Some(0_u32))).unwrap(), vec![1, 1, 0]);
+        assert_eq!(serialize(&Some(Some(5_u32))).unwrap(), vec![1, 1, 5]);
+    }
+
+    struct UnitVariant(u32);
+
+    impl Serialize for UnitVariant {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_unit_variant("UnitVariant", self.0, "Variant")
+        }
+    }
+
+    #[test]
+    fn serialize_small_unit_variants_as_single_byte() {
+        assert_eq!(serialize(&UnitVariant(0)).unwrap(), [0]);
+        assert_eq!(serialize(&UnitVariant(127)).unwrap(), [127]);
+        assert_eq!(serialize(&UnitVariant(128)).unwrap(), [128, 1]);
+        assert_eq!(serialize(&UnitVariant(16383)).unwrap(), [255, 127]);
+    }
+
+    #[derive(Serialize)]
+    struct Marker;
+
+    #[derive(Serialize)]
+    struct Marked {
+        before: u8,
+        marker: Marker,
+        after: u8,
+    }
+
+    #[derive(Serialize)]
+    enum MarkerEnum {
+        Unit,
+        Marker(Marker),
+        Value(u8),
+    }
+
+    #[test]
+    fn serialize_unit_structs() {
+        assert!(serialize(&Marker).unwrap().is_empty());
+        assert_eq!(
+            serialize(&Marked {
+                before: 1,
+                marker: Marker,
+                after: 2
+            })
+            .unwrap(),
+            [1, 2]
+        );
+        assert_eq!(serialize(&MarkerEnum::Unit).unwrap(), [0]);
+        assert_eq!(serialize(&MarkerEnum::Marker(Marker)).unwrap(), [1]);
+        assert_eq!(serialize(&MarkerEnum::Value(7)).unwrap(), [2, 7]);
+    }
+
+    #[derive(Serialize)]
+    struct Plain {
+        before: u8,
+        after: u16,
+    }
+
+    #[derive(Serialize)]
+    struct Phantom<T> {
+        before: u8,
+        marker: std::marker::PhantomData<T>,
+        after: u16,
+    }
+
+    #[test]
+    fn serialize_phantom_data() {
+        assert!(serialize(&std::marker::PhantomData::<u64>)
+            .unwrap()
+            .is_empty());
+        let plain = serialize(&Plain {
+            before: 1,
+            after: 300,
+        })
+        .unwrap();
+        assert_eq!(plain, [1, 172, 2]);
+        assert_eq!(
+            serialize(&Phantom::<String> {
+                before: 1,
+                marker: std::marker::PhantomData,
+                after: 300
+            })
+            .unwrap(),
+            plain
+        );
+    }
+
     #[test]
     fn serialize_option() {
         let missing: Option<u8> = None;
@@ -526,6 +2339,25 @@ mod tests {
         assert_eq!(serialize(&missing).unwrap(), vec![0]);
     }
 
+    #[test]
+    fn serialize_optional_collections() {
+        assert_eq!(serialize(&None::<Vec<u8>>).unwrap(), [0]);
+        assert_eq!(serialize(&Some(Vec::<u8>::new())).unwrap(), [1, 0]);
+        assert_eq!(serialize(&Some(vec![0_u8])).unwrap(), [1, 1, 0]);
+
+        assert_eq!(serialize(&None::<String>).unwrap(), [0]);
+        assert_eq!(serialize(&Some(String::new())).unwrap(), [1, 0]);
+        assert_eq!(serialize(&Some("a")).unwrap(), [1, 1, 97]);
+
+        let set = crate::Set::from(vec!["b", "a"]);
+        assert_eq!(serialize(&None::<crate::Set<&str>>).unwrap(), [0]);
+        assert_eq!(
+            serialize(&Some(crate::Set::<&str>::from(Vec::new()))).unwrap(),
+            [1, 0]
+        );
+        assert_eq!(serialize(&Some(set)).unwrap(), [1, 2, 1, 97, 1, 98]);
+    }
+
     #[derive(Debug, Serialize, PartialEq)]
     struct Struct {
         int: u16,
@@ -534,6 +2366,70 @@ mod tests {
         boolean: bool,
     }
 
+    #[derive(Serialize)]
+    struct Sparse {
+        first: u8,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        middle: Option<u8>,
+        last: u8,
+    }
+
+    #[derive(Serialize)]
+    enum SparseVariant {
+        Struct {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            field: Option<u8>,
+        },
+    }
+
+    #[test]
+    fn serialize_skipped_fields() {
+        let value = Sparse {
+            first: 1,
+            middle: Some(2),
+            last: 3,
+        };
+        assert_eq!(serialize(&value).unwrap(), [1, 1, 2, 3]);
+
+        let value = Sparse {
+            first: 1,
+            middle: None,
+            last: 3,
+        };
+        let err = serialize(&value).unwrap_err();
+        assert_eq!(err.path(), ["middle"]);
+        assert_eq!(err.root_cause(), &CordError::NotSupported("skipped field"));
+
+        let value = SparseVariant::Struct { field: None };
+        assert_eq!(serialize(&value).unwrap_err().path(), ["field"]);
+    }
+
+    #[test]
+    fn serialize_struct_encodings() {
+        let value = Struct {
+            int: 99,
+            option: None,
+            seq: vec![],
+            boolean: true,
+        };
+        assert_eq!(serialize(&value).unwrap(), [99, 0, 0, 1]);
+
+        for encoding in [
+            StructEncoding::LengthPrefixed,
+            StructEncoding::StrictLengthPrefixed,
+        ] {
+            let options = Options::new().struct_encoding(encoding);
+            assert_eq!(
+                serialize_with_options(&value, options).unwrap(),
+                [4, 99, 0, 0, 1]
+            );
+            assert_eq!(
+                serialize_with_options(&Enum::Struct { field: 7 }, options).unwrap(),
+                [3, 1, 7]
+            );
+        }
+    }
+
     #[test]
     fn serialize_struct() {
         assert_eq!(
@@ -556,6 +2452,82 @@ mod tests {
     }
 
     #[test]
+    fn serialize_map_in_canonical_order() {
+        let mut first: HashMap<String, u8> = HashMap::new();
+        let mut second: HashMap<String, u8> = HashMap::new();
+        for (key, value) in [("b", 2), ("aa", 3), ("a", 1)] {
+            first.insert(key.to_string(), value);
+        }
+        for (key, value) in [("a", 1), ("b", 2), ("aa", 3)] {
+            second.insert(key.to_string(), value);
+        }
+
+        let expected = vec![
+            3, // Serialize the number of entries
+            1, 97, 1, // Serialize "a" => 1
+            1, 98, 2, // Serialize "b" => 2
+            2, 97, 97, 3, // Serialize "aa" => 3
+        ];
+        assert_eq!(serialize(&first).unwrap(), expected);
+        assert_eq!(serialize(&second).unwrap(), expected);
+
+        let btree: BTreeMap<String, u8> = first.into_iter().collect();
+        assert_eq!(serialize(&btree).unwrap(), expected);
+    }
+
+    #[test]
+    fn serialize_map_trusting_key_ord() {
+        let map: BTreeMap<String, u8> = vec![(String::from("aa"), 1), (String::from("b"), 2)]
+            .into_iter()
+            .collect();
+        assert_eq!(serialize(&map).unwrap(), [2, 1, 98, 2, 2, 97, 97, 1]);
+
+        let options = Options::new().trust_key_ord();
+        assert_eq!(
+            serialize_with_options(&map, options).unwrap(),
+            [2, 2, 97, 97, 1, 1, 98, 2]
+        );
+        assert_eq!(
+            serialize_with_options(&map, options.seq_encoding(SeqEncoding::Terminated)).unwrap(),
+            [1, 2, 97, 97, 1, 1, 1, 98, 2, 0]
+        );
+        assert_eq!(
+            serialize_with_options(&BTreeMap::<u8, u8>::new(), options.streaming_seq()).unwrap(),
+            [0]
+        );
+    }
+
+    #[test]
+    fn serialize_empty_map() {
+        assert_eq!(serialize(&HashMap::<u8, u8>::new()).unwrap(), [0]);
+    }
+
+    #[derive(Serialize)]
+    struct Inbox {
+        owner: u8,
+        messages: Vec<String>,
+        labels: HashMap<String, u8>,
+        attachment: Bytes,
+    }
+
+    #[test]
+    fn serialize_empty_collections() {
+        assert_eq!(serialize(&crate::Set::<u8>::from(Vec::new())).unwrap(), [0]);
+        assert_eq!(serialize(&Bytes::from(Vec::new())).unwrap(), [0]);
+        assert_eq!(
+            serialize(&Inbox {
+                owner: 7,
+                messages: Vec::new(),
+                labels: HashMap::new(),
+                attachment: Bytes::from(Vec::new()),
+            })
+            .unwrap(),
+            [7, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    #[allow(clippy::approx_constant)]
     fn serialize_unsupported_f64() {
         let value: f64 = 2.71828;
         assert_eq!(
@@ -564,6 +2536,48 @@ mod tests {
         );
     }
 
+    #[derive(Serialize)]
+    struct Inner {
+        timestamp: f64,
+    }
+
+    #[derive(Serialize)]
+    struct Outer {
+        id: u8,
+        inner: Inner,
+    }
+
+    #[test]
+    fn serialize_unsupported_reports_field_path() {
+        let error = serialize(&Outer {
+            id: 1,
+            inner: Inner { timestamp: 1.5 },
+        })
+        .unwrap_err();
+
+        assert_eq!(error.path(), ["inner", "timestamp"]);
+        assert_eq!(error.root_cause(), &CordError::NotSupported("f64"));
+        assert_eq!(
+            error.to_string(),
+            "Cord does not support: f64 at inner.timestamp"
+        );
+    }
+
+    #[derive(Serialize)]
+    struct Metadata {
+        metadata: HashMap<String, f64>,
+    }
+
+    #[test]
+    fn serialize_unsupported_in_map_reports_field_path() {
+        let mut metadata = HashMap::new();
+        metadata.insert(String::from("score"), 0.5);
+        let error = serialize(&Metadata { metadata }).unwrap_err();
+
+        assert_eq!(error.path(), ["metadata"]);
+        assert_eq!(error.to_string(), "Cord does not support: f64 at metadata");
+    }
+
     #[test]
     fn serialize_unsupported_char() {
         let value: char = 'A';
@@ -0,0 +1,57 @@
+use crate::{deserialize_with_options, serialize_with_options, Options};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::Debug;
+
+/// Asserts that a value encodes to exactly the given bytes, and that those bytes decode back
+/// to an equal value. Options may be passed as a third argument.
+///
+/// ```ignore
+/// cord_snapshot!(String::from("test"), [4, 116, 101, 115, 116]);
+/// cord_snapshot!(7_u16, [0, 7], Options::order_preserving());
+/// ```
+#[macro_export]
+macro_rules! cord_snapshot {
+    ($value:expr, $expected:expr $(,)?) => {
+        $crate::assert_snapshot(&$value, &$expected, $crate::Options::default())
+    };
+    ($value:expr, $expected:expr, $options:expr $(,)?) => {
+        $crate::assert_snapshot(&$value, &$expected, $options)
+    };
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_snapshot<T>(value: &T, expected: &[u8], options: Options)
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let bytes = serialize_with_options(value, options)
+        .unwrap_or_else(|err| panic!("{value:?} failed to serialize: {err}"));
+    assert_eq!(bytes, expected, "encoding of {value:?} changed");
+
+    let decoded: T = deserialize_with_options(&bytes, options)
+        .unwrap_or_else(|err| panic!("{value:?} failed to deserialize: {err}"));
+    assert_eq!(&decoded, value, "{value:?} did not survive a round trip");
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Options, Set};
+
+    #[test]
+    fn snapshot_matches() {
+        cord_snapshot!(String::from("test"), [4, 116, 101, 115, 116]);
+        cord_snapshot!(7_u16, [0, 7], Options::order_preserving());
+        cord_snapshot!(
+            Set::from(vec![String::from("aa"), String::from("z")]),
+            [2, 1, 122, 2, 97, 97]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "encoding of 300 changed")]
+    fn snapshot_detects_changes() {
+        cord_snapshot!(300_u16, [172, 3]);
+    }
+}
@@ -0,0 +1,88 @@
+//! Encodes `SystemTime` like [`DateTime`](crate::DateTime), for use with
+//! `#[serde(with = "cord::system_time")]`.
+//!
+//! serde's own encoding of `SystemTime` rejects times before the epoch and differs from that
+//! of `DateTime`. Times are truncated to millisecond precision.
+
+use crate::DateTime;
+use serde::{ser, Deserialize, Deserializer, Serialize, Serializer};
+use std::time::SystemTime;
+
+pub fn serialize<S>(time: &SystemTime, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    DateTime::try_from(*time)
+        .map_err(ser::Error::custom)?
+        .serialize(serializer)
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<SystemTime, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    DateTime::deserialize(deserializer).map(SystemTime::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{deserialize, serialize, DateTime};
+    use serde::{Deserialize, Serialize};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Event {
+        #[serde(with = "crate::system_time")]
+        at: SystemTime,
+    }
+
+    #[test]
+    fn system_time_matches_datetime() {
+        let event = Event {
+            at: UNIX_EPOCH + Duration::from_millis(1_696_516_200_000),
+        };
+        let datetime: DateTime = "2023-10-05T14:30:00Z".parse().unwrap();
+
+        let bytes = serialize(&event).unwrap();
+        assert_eq!(bytes, serialize(&datetime).unwrap());
+        assert_eq!(deserialize::<Event>(&bytes).unwrap(), event);
+    }
+
+    #[test]
+    fn system_time_before_epoch() {
+        let event = Event {
+            at: UNIX_EPOCH - Duration::from_millis(1_500),
+        };
+        let datetime: DateTime = "1969-12-31T23:59:58.500Z".parse().unwrap();
+
+        let bytes = serialize(&event).unwrap();
+        assert_eq!(bytes, serialize(&datetime).unwrap());
+        assert_eq!(deserialize::<Event>(&bytes).unwrap(), event);
+    }
+
+    #[test]
+    fn system_time_truncates_to_millis() {
+        let event = Event {
+            at: UNIX_EPOCH - Duration::from_micros(1_500),
+        };
+        let bytes = serialize(&event).unwrap();
+        assert_eq!(
+            deserialize::<Event>(&bytes).unwrap().at,
+            UNIX_EPOCH - Duration::from_millis(2)
+        );
+
+        let at = UNIX_EPOCH + Duration::from_micros(1_500);
+        assert_eq!(
+            DateTime::try_from(at).unwrap(),
+            DateTime::try_from(UNIX_EPOCH + Duration::from_millis(1)).unwrap()
+        );
+    }
+
+    #[test]
+    fn system_time_out_of_range() {
+        let event = Event {
+            at: UNIX_EPOCH + Duration::from_secs(u64::MAX / 2),
+        };
+        assert!(serialize(&event).is_err());
+    }
+}
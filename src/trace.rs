@@ -0,0 +1,116 @@
+// Emits a `log` record under the `cord` target when the `trace` feature is enabled, and
+// compiles to nothing otherwise.
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "trace")]
+        log::trace!(target: "cord", $($arg)*);
+    };
+}
+
+pub(crate) use trace;
+
+#[cfg(all(test, feature = "trace"))]
+mod tests {
+    use crate::{deserialize, serialize, serialize_with_options, Options};
+    use log::{LevelFilter, Log, Metadata, Record};
+    use serde::{Deserialize, Serialize};
+    use std::cell::RefCell;
+
+    thread_local! {
+        static RECORDS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    }
+
+    // Records are kept per thread, so that tests running alongside do not interleave
+    struct Capture;
+
+    impl Log for Capture {
+        fn enabled(&self, metadata: &Metadata) -> bool {
+            metadata.target() == "cord"
+        }
+
+        fn log(&self, record: &Record) {
+            if self.enabled(record.metadata()) {
+                RECORDS.with(|records| records.borrow_mut().push(record.args().to_string()));
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    static CAPTURE: Capture = Capture;
+
+    fn capture(f: impl FnOnce()) -> Vec<String> {
+        let _ = log::set_logger(&CAPTURE);
+        log::set_max_level(LevelFilter::Trace);
+        RECORDS.with(|records| records.borrow_mut().clear());
+        f();
+        RECORDS.with(|records| records.take())
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Shape {
+        Circle { radius: u16, tags: Vec<u8> },
+    }
+
+    #[test]
+    fn trace_fields_variants_and_elements() {
+        let shape = Shape::Circle {
+            radius: 300,
+            tags: vec![1],
+        };
+
+        let records = capture(|| assert_eq!(serialize(&shape).unwrap(), [0, 172, 2, 1, 1]));
+        assert_eq!(
+            records,
+            [
+                "serializing variant 0 at offset 0",
+                "serializing field radius at offset 1",
+                "serializing field tags at offset 3",
+                "serializing element at offset 4",
+            ]
+        );
+
+        let records = capture(|| {
+            assert_eq!(deserialize::<Shape>(&[0, 172, 2, 1, 1]).unwrap(), shape);
+        });
+        assert_eq!(
+            records,
+            [
+                "deserializing variant at offset 0",
+                "deserializing field radius at offset 1",
+                "deserializing field tags at offset 3",
+                "deserializing element at offset 4",
+            ]
+        );
+    }
+
+    #[derive(Serialize)]
+    struct Reading {
+        sensor: u16,
+        samples: Vec<u8>,
+    }
+
+    #[test]
+    fn trace_serializer_offsets() {
+        let reading = Reading {
+            sensor: 7,
+            samples: vec![1, 2],
+        };
+        let options = Options::order_preserving();
+        let records = capture(|| {
+            assert_eq!(
+                serialize_with_options(&reading, options).unwrap(),
+                [0, 7, 1, 1, 1, 2, 0]
+            );
+        });
+        assert_eq!(
+            records,
+            [
+                "serializing field sensor at offset 0",
+                "serializing field samples at offset 2",
+                "serializing element at offset 3",
+                "serializing element at offset 5",
+            ]
+        );
+    }
+}
@@ -1,16 +1,46 @@
 use crate::{CordError, CordResult};
-use std::collections::HashSet;
+use chrono::SubsecRound;
+use std::borrow::{Borrow, Cow};
+use std::collections::{BTreeSet, HashSet};
 use std::hash::Hash;
 use std::iter::FromIterator;
+use std::marker::PhantomData;
 use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+/// An owned byte string, encoded with a length prefix like a `&[u8]`.
+///
+/// Not to be confused with `bytes::Bytes`, which shares its encoding when the `bytes`
+/// feature is enabled and converts to and from this type.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Bytes(pub(crate) Vec<u8>);
 
 impl Bytes {
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn into_vec(self) -> Vec<u8> {
+        self.0
+    }
+
     pub fn to_vec(&self) -> Vec<u8> {
         self.0.clone()
     }
+
+    /// Copies `slice`, rejecting it if longer than `max_len` bytes.
+    pub fn try_new(slice: &[u8], max_len: usize) -> CordResult<Self> {
+        if slice.len() > max_len {
+            return Err(CordError::ValidationError("byte string too long"));
+        }
+        Ok(Bytes(slice.to_vec()))
+    }
+}
+
+impl From<&[u8]> for Bytes {
+    fn from(slice: &[u8]) -> Self {
+        Bytes(slice.to_vec())
+    }
 }
 
 impl From<Vec<u8>> for Bytes {
@@ -31,6 +61,74 @@ impl From<&Bytes> for Vec<u8> {
     }
 }
 
+impl Borrow<[u8]> for Bytes {
+    fn borrow(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl PartialEq<[u8]> for Bytes {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<Vec<u8>> for Bytes {
+    fn eq(&self, other: &Vec<u8>) -> bool {
+        &self.0 == other
+    }
+}
+
+/// A byte string that borrows from the input it was decoded from where the encoding allows,
+/// rather than copying like [`Bytes`]. Both share the same encoding.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BorrowedBytes<'a>(pub(crate) Cow<'a, [u8]>);
+
+impl<'a> BorrowedBytes<'a> {
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Whether the bytes reference the input, which is not possible for escaped encodings.
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self.0, Cow::Borrowed(_))
+    }
+
+    pub fn into_owned(self) -> Bytes {
+        Bytes(self.0.into_owned())
+    }
+}
+
+impl<'a> From<&'a [u8]> for BorrowedBytes<'a> {
+    fn from(slice: &'a [u8]) -> Self {
+        Self(Cow::Borrowed(slice))
+    }
+}
+
+impl From<BorrowedBytes<'_>> for Bytes {
+    fn from(bytes: BorrowedBytes<'_>) -> Self {
+        bytes.into_owned()
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl From<bytes::Bytes> for Bytes {
+    fn from(bytes: bytes::Bytes) -> Self {
+        Self(bytes.into())
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl From<Bytes> for bytes::Bytes {
+    fn from(bytes: Bytes) -> Self {
+        bytes.0.into()
+    }
+}
+
+/// An unordered collection encoded as a sequence sorted by the encoded bytes of its elements.
+///
+/// The order compares complete encodings, length prefixes included, so shorter strings
+/// precede longer ones: `"z"` sorts before `"aa"`.
 #[derive(Debug, Clone)]
 pub struct Set<T> {
     pub hashset: HashSet<T>,
@@ -45,12 +143,36 @@ where
     }
 }
 
+impl<T: serde::Serialize> Set<T> {
+    /// Compares two sets by their encodings, without requiring `T: Hash + Eq`. Fails if
+    /// either set fails to serialize.
+    pub fn canonical_eq(&self, other: &Self) -> CordResult<bool> {
+        let mut scratch = Vec::new();
+        crate::ser::encode_element(&mut scratch, self)?;
+        let len = scratch.len();
+        crate::ser::encode_element(&mut scratch, other)?;
+        Ok(scratch[..len] == scratch[len..])
+    }
+}
+
 impl<T: Clone> From<&Set<T>> for Vec<T> {
     fn from(set: &Set<T>) -> Self {
         set.hashset.iter().cloned().collect()
     }
 }
 
+impl<T> From<Set<T>> for Vec<T> {
+    fn from(set: Set<T>) -> Self {
+        set.hashset.into_iter().collect()
+    }
+}
+
+impl<T> From<Set<T>> for HashSet<T> {
+    fn from(set: Set<T>) -> Self {
+        set.hashset
+    }
+}
+
 impl<T> From<HashSet<T>> for Set<T> {
     fn from(hashset: HashSet<T>) -> Self {
         Self { hashset }
@@ -72,6 +194,45 @@ impl<T: Hash + Eq> FromIterator<T> for Set<T> {
     }
 }
 
+/// A [`Set`] backed by a `BTreeSet`, so that iteration follows `T`'s ordering.
+///
+/// It shares `Set`'s wire format: elements are written in the order of their encoded bytes,
+/// which may differ from the order of `T`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortedSet<T> {
+    pub btreeset: BTreeSet<T>,
+}
+
+impl<T> From<BTreeSet<T>> for SortedSet<T> {
+    fn from(btreeset: BTreeSet<T>) -> Self {
+        Self { btreeset }
+    }
+}
+
+impl<T> From<SortedSet<T>> for BTreeSet<T> {
+    fn from(set: SortedSet<T>) -> Self {
+        set.btreeset
+    }
+}
+
+impl<T> From<SortedSet<T>> for Vec<T> {
+    fn from(set: SortedSet<T>) -> Self {
+        set.btreeset.into_iter().collect()
+    }
+}
+
+impl<T: Ord> From<Vec<T>> for SortedSet<T> {
+    fn from(vector: Vec<T>) -> Self {
+        SortedSet::from_iter(vector)
+    }
+}
+
+impl<T: Ord> FromIterator<T> for SortedSet<T> {
+    fn from_iter<E: IntoIterator<Item = T>>(iter: E) -> Self {
+        SortedSet::from(BTreeSet::from_iter(iter))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd)]
 pub struct DateTime {
     pub chrono: chrono::DateTime<chrono::Utc>,
@@ -83,15 +244,63 @@ impl DateTime {
             chrono: chrono::Utc::now(),
         }
     }
+
+    /// Drops any fraction of a second, towards the past.
+    pub fn truncate_to_seconds(&self) -> Self {
+        self.chrono.trunc_subsecs(0).into()
+    }
+
+    /// Drops any fraction of a millisecond, towards the past.
+    pub fn truncate_to_millis(&self) -> Self {
+        self.chrono.trunc_subsecs(3).into()
+    }
+}
+
+const NAIVE_DATETIME_FORMATS: [&str; 2] = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"];
+
+impl DateTime {
+    /// Parses `s` with a `chrono` format string, assuming UTC when `fmt` carries no offset.
+    pub fn parse_with_format(s: &str, fmt: &str) -> CordResult<Self> {
+        chrono::DateTime::parse_from_str(s, fmt)
+            .map(|chrono| chrono.with_timezone(&chrono::Utc))
+            .or_else(|offset_err| {
+                chrono::NaiveDateTime::parse_from_str(s, fmt)
+                    .map(|naive| naive.and_utc())
+                    // Formats without an offset always fail the first attempt for want of one
+                    .map_err(|naive_err| match offset_err.kind() {
+                        chrono::format::ParseErrorKind::NotEnough => naive_err,
+                        _ => offset_err,
+                    })
+            })
+            .map(Self::from)
+            .map_err(|err| parse_error(s, err))
+    }
+}
+
+fn parse_error(input: &str, err: chrono::ParseError) -> CordError {
+    CordError::ParseError {
+        input: input.to_owned(),
+        detail: err.to_string(),
+    }
 }
 
 impl FromStr for DateTime {
     type Err = CordError;
 
     fn from_str(s: &str) -> CordResult<Self, Self::Err> {
-        let chrono = chrono::DateTime::<chrono::Utc>::from_str(s)
-            .map_err(|_| CordError::ValidationError("Failed to parse datetime"))?;
-        Ok(Self { chrono })
+        let rfc3339_err = match chrono::DateTime::<chrono::Utc>::from_str(s) {
+            Ok(chrono) => return Ok(Self { chrono }),
+            Err(err) => err,
+        };
+
+        if let Ok(chrono) = chrono::DateTime::parse_from_rfc2822(s) {
+            return Ok(chrono.with_timezone(&chrono::Utc).into());
+        }
+
+        NAIVE_DATETIME_FORMATS
+            .iter()
+            .find_map(|fmt| Self::parse_with_format(s, fmt).ok())
+            .ok_or_else(|| parse_error(s, rfc3339_err))
     }
 }
 
@@ -100,3 +309,581 @@ impl From<chrono::DateTime<chrono::Utc>> for DateTime {
         Self { chrono }
     }
 }
+
+impl From<DateTime> for chrono::DateTime<chrono::Utc> {
+    fn from(datetime: DateTime) -> Self {
+        datetime.chrono
+    }
+}
+
+impl From<&DateTime> for chrono::DateTime<chrono::Utc> {
+    fn from(datetime: &DateTime) -> Self {
+        datetime.chrono
+    }
+}
+
+/// Truncates to millisecond precision. Times before the epoch round towards the past, matching
+/// the encoding of `DateTime`.
+impl TryFrom<SystemTime> for DateTime {
+    type Error = CordError;
+
+    fn try_from(time: SystemTime) -> CordResult<Self> {
+        let millis = match time.duration_since(UNIX_EPOCH) {
+            Ok(after) => i64::try_from(after.as_millis()).ok(),
+            Err(err) => {
+                let before = err.duration();
+                let partial = before.subsec_nanos() % 1_000_000 != 0;
+                i64::try_from(before.as_millis()).ok().map(|millis| {
+                    if partial {
+                        -millis - 1
+                    } else {
+                        -millis
+                    }
+                })
+            }
+        };
+
+        millis
+            .and_then(Millis::from_units)
+            .map(Self::from)
+            .ok_or(CordError::ValidationError("SystemTime out of range"))
+    }
+}
+
+impl From<DateTime> for SystemTime {
+    fn from(datetime: DateTime) -> Self {
+        datetime.chrono.into()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DateTimeWithOffset {
+    pub chrono: chrono::DateTime<chrono::FixedOffset>,
+}
+
+impl PartialEq for DateTimeWithOffset {
+    fn eq(&self, other: &Self) -> bool {
+        self.chrono == other.chrono && self.chrono.offset() == other.chrono.offset()
+    }
+}
+
+impl Eq for DateTimeWithOffset {}
+
+impl From<chrono::DateTime<chrono::FixedOffset>> for DateTimeWithOffset {
+    fn from(chrono: chrono::DateTime<chrono::FixedOffset>) -> Self {
+        Self { chrono }
+    }
+}
+
+impl From<&DateTimeWithOffset> for DateTime {
+    fn from(datetime: &DateTimeWithOffset) -> Self {
+        datetime.chrono.with_timezone(&chrono::Utc).into()
+    }
+}
+
+/// A calendar date encoded as the number of days since the Unix epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub chrono: chrono::NaiveDate,
+}
+
+impl Date {
+    /// Parses `s` with a `chrono` format string.
+    pub fn parse_with_format(s: &str, fmt: &str) -> CordResult<Self> {
+        chrono::NaiveDate::parse_from_str(s, fmt)
+            .map(Self::from)
+            .map_err(|err| parse_error(s, err))
+    }
+}
+
+/// Accepts ISO 8601 dates such as `2023-10-05`.
+impl FromStr for Date {
+    type Err = CordError;
+
+    fn from_str(s: &str) -> CordResult<Self, Self::Err> {
+        chrono::NaiveDate::from_str(s)
+            .map(Self::from)
+            .map_err(|err| parse_error(s, err))
+    }
+}
+
+impl From<chrono::NaiveDate> for Date {
+    fn from(chrono: chrono::NaiveDate) -> Self {
+        Self { chrono }
+    }
+}
+
+impl From<Date> for chrono::NaiveDate {
+    fn from(date: Date) -> Self {
+        date.chrono
+    }
+}
+
+/// A time of day encoded as milliseconds since midnight, truncating anything finer.
+/// Leap seconds cannot be encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Time {
+    pub chrono: chrono::NaiveTime,
+}
+
+impl Time {
+    /// Parses `s` with a `chrono` format string.
+    pub fn parse_with_format(s: &str, fmt: &str) -> CordResult<Self> {
+        chrono::NaiveTime::parse_from_str(s, fmt)
+            .map(Self::from)
+            .map_err(|err| parse_error(s, err))
+    }
+}
+
+/// Accepts `HH:MM:SS` with optional fractional seconds, such as `14:30:00.250`.
+impl FromStr for Time {
+    type Err = CordError;
+
+    fn from_str(s: &str) -> CordResult<Self, Self::Err> {
+        Self::parse_with_format(s, "%H:%M:%S%.f")
+    }
+}
+
+impl From<chrono::NaiveTime> for Time {
+    fn from(chrono: chrono::NaiveTime) -> Self {
+        Self { chrono }
+    }
+}
+
+impl From<Time> for chrono::NaiveTime {
+    fn from(time: Time) -> Self {
+        time.chrono
+    }
+}
+
+/// A date and time without an offset, encoded like a [`DateTime`] in UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NaiveDateTime {
+    pub chrono: chrono::NaiveDateTime,
+}
+
+impl NaiveDateTime {
+    /// Parses `s` with a `chrono` format string.
+    pub fn parse_with_format(s: &str, fmt: &str) -> CordResult<Self> {
+        chrono::NaiveDateTime::parse_from_str(s, fmt)
+            .map(Self::from)
+            .map_err(|err| parse_error(s, err))
+    }
+}
+
+/// Accepts the naive formats of [`DateTime`], such as `2023-10-05T14:30:00`.
+impl FromStr for NaiveDateTime {
+    type Err = CordError;
+
+    fn from_str(s: &str) -> CordResult<Self, Self::Err> {
+        let iso_err = match chrono::NaiveDateTime::from_str(s) {
+            Ok(chrono) => return Ok(Self { chrono }),
+            Err(err) => err,
+        };
+
+        NAIVE_DATETIME_FORMATS
+            .iter()
+            .find_map(|fmt| Self::parse_with_format(s, fmt).ok())
+            .ok_or_else(|| parse_error(s, iso_err))
+    }
+}
+
+impl From<chrono::NaiveDateTime> for NaiveDateTime {
+    fn from(chrono: chrono::NaiveDateTime) -> Self {
+        Self { chrono }
+    }
+}
+
+impl From<NaiveDateTime> for chrono::NaiveDateTime {
+    fn from(datetime: NaiveDateTime) -> Self {
+        datetime.chrono
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// The unit a [`Timestamp`] counts in, one of [`Seconds`], [`Millis`], [`Micros`] and [`Nanos`].
+pub trait Precision: private::Sealed {
+    const UNITS_PER_SECOND: i64;
+
+    fn to_units(chrono: &chrono::DateTime<chrono::Utc>) -> Option<i64> {
+        let nanos_per_unit = 1_000_000_000 / Self::UNITS_PER_SECOND;
+        chrono
+            .timestamp()
+            .checked_mul(Self::UNITS_PER_SECOND)?
+            .checked_add(i64::from(chrono.timestamp_subsec_nanos()) / nanos_per_unit)
+    }
+
+    fn from_units(units: i64) -> Option<chrono::DateTime<chrono::Utc>> {
+        let nanos_per_unit = 1_000_000_000 / Self::UNITS_PER_SECOND;
+        let seconds = units.div_euclid(Self::UNITS_PER_SECOND);
+        let nanos = units.rem_euclid(Self::UNITS_PER_SECOND) * nanos_per_unit;
+        chrono::DateTime::<chrono::Utc>::from_timestamp(seconds, nanos as u32)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Seconds;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Millis;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Micros;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Nanos;
+
+impl private::Sealed for Seconds {}
+
+impl Precision for Seconds {
+    const UNITS_PER_SECOND: i64 = 1;
+}
+
+impl private::Sealed for Millis {}
+
+impl Precision for Millis {
+    const UNITS_PER_SECOND: i64 = 1_000;
+}
+
+impl private::Sealed for Micros {}
+
+impl Precision for Micros {
+    const UNITS_PER_SECOND: i64 = 1_000_000;
+}
+
+impl private::Sealed for Nanos {}
+
+impl Precision for Nanos {
+    const UNITS_PER_SECOND: i64 = 1_000_000_000;
+}
+
+/// A UTC timestamp encoded as a count of `P` units since the Unix epoch.
+///
+/// Anything finer than `P` is truncated on the wire, and both ends must agree on `P`.
+/// `Timestamp<Millis>` is byte-compatible with [`DateTime`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd)]
+pub struct Timestamp<P: Precision> {
+    pub chrono: chrono::DateTime<chrono::Utc>,
+    precision: PhantomData<P>,
+}
+
+impl<P: Precision> Timestamp<P> {
+    pub fn now() -> Self {
+        chrono::Utc::now().into()
+    }
+}
+
+impl<P: Precision> From<chrono::DateTime<chrono::Utc>> for Timestamp<P> {
+    fn from(chrono: chrono::DateTime<chrono::Utc>) -> Self {
+        Self {
+            chrono,
+            precision: PhantomData,
+        }
+    }
+}
+
+impl<P: Precision> From<DateTime> for Timestamp<P> {
+    fn from(datetime: DateTime) -> Self {
+        datetime.chrono.into()
+    }
+}
+
+/// Elements already in [`Set`]'s canonical order, which are written as they are rather than
+/// sorted again. Both share the same encoding.
+///
+/// The order is trusted in release builds, and checked in debug builds, where elements out of
+/// order fail to serialize. Decoding keeps the elements in their encoded order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PresortedSet<T>(pub Vec<T>);
+
+impl<T> From<Vec<T>> for PresortedSet<T> {
+    fn from(vector: Vec<T>) -> Self {
+        Self(vector)
+    }
+}
+
+impl<T> From<PresortedSet<T>> for Vec<T> {
+    fn from(set: PresortedSet<T>) -> Self {
+        set.0
+    }
+}
+
+// Reserved name under which sets are written, so that Cord's deserializer can check the order
+// of their elements against the input
+pub(crate) const SET: &str = "$cord::Set";
+
+// Reserved name through which `RawBytes` asks Cord's (de)serializer to write its tuple of
+// bytes as they are, with no length prefix
+pub(crate) const RAW_BYTES: &str = "$cord::RawBytes";
+
+/// Exactly `N` bytes, written as they are with no length prefix since the length is part of
+/// the schema. Other formats see a tuple of `N` bytes, as they would a `[u8; N]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RawBytes<const N: usize>(pub [u8; N]);
+
+impl<const N: usize> From<[u8; N]> for RawBytes<N> {
+    fn from(array: [u8; N]) -> Self {
+        Self(array)
+    }
+}
+
+impl<const N: usize> From<RawBytes<N>> for [u8; N] {
+    fn from(bytes: RawBytes<N>) -> Self {
+        bytes.0
+    }
+}
+
+/// A non-decreasing sequence of unsigned integers, encoded as its first element followed by
+/// the difference between each element and the one before it.
+///
+/// Sorted identifiers that lie close together thus take a byte or two each, regardless of
+/// their magnitude. Sequences that decrease anywhere fail to serialize.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeltaSeq<T>(pub Vec<T>);
+
+impl<T> From<Vec<T>> for DeltaSeq<T> {
+    fn from(vector: Vec<T>) -> Self {
+        Self(vector)
+    }
+}
+
+impl<T> From<DeltaSeq<T>> for Vec<T> {
+    fn from(seq: DeltaSeq<T>) -> Self {
+        seq.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Bytes, CordError, Date, DateTime, DateTimeWithOffset, NaiveDateTime, Set, Time};
+    use chrono::Utc;
+    use std::collections::{BTreeSet, HashMap, HashSet};
+    use std::str::FromStr;
+
+    #[test]
+    fn set_canonical_eq() {
+        let first: Set<&str> = vec!["aa", "z"].into_iter().collect();
+        let second: Set<&str> = vec!["z", "aa"].into_iter().collect();
+        let third: Set<&str> = vec!["z"].into_iter().collect();
+        assert!(first.canonical_eq(&second).unwrap());
+        assert!(!first.canonical_eq(&third).unwrap());
+
+        let chars: Set<char> = vec!['a'].into_iter().collect();
+        assert!(chars.canonical_eq(&chars).is_err());
+    }
+
+    #[test]
+    fn truncate_datetime() {
+        let datetime = DateTime::from_str("2023-10-05T14:30:00.123456Z").unwrap();
+        assert_eq!(
+            datetime.truncate_to_seconds(),
+            DateTime::from_str("2023-10-05T14:30:00Z").unwrap()
+        );
+        assert_eq!(
+            datetime.truncate_to_millis(),
+            DateTime::from_str("2023-10-05T14:30:00.123Z").unwrap()
+        );
+
+        let datetime = DateTime::from_str("1969-12-31T23:59:59.500Z").unwrap();
+        assert_eq!(
+            datetime.truncate_to_seconds(),
+            DateTime::from_str("1969-12-31T23:59:59Z").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_date() {
+        let expected = Date::from(chrono::NaiveDate::from_ymd_opt(2023, 10, 5).unwrap());
+        assert_eq!(Date::from_str("2023-10-05").unwrap(), expected);
+        assert_eq!(
+            Date::parse_with_format("05/10/2023", "%d/%m/%Y").unwrap(),
+            expected
+        );
+        assert_eq!(
+            Date::from_str("2023-02-30").unwrap_err(),
+            CordError::ParseError {
+                input: String::from("2023-02-30"),
+                detail: String::from("input is out of range"),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_time() {
+        assert_eq!(
+            Time::from_str("14:30:00").unwrap().chrono,
+            chrono::NaiveTime::from_hms_opt(14, 30, 0).unwrap()
+        );
+        assert_eq!(
+            Time::from_str("14:30:00.250").unwrap().chrono,
+            chrono::NaiveTime::from_hms_milli_opt(14, 30, 0, 250).unwrap()
+        );
+        assert!(matches!(
+            Time::from_str("24:00:00").unwrap_err(),
+            CordError::ParseError { .. }
+        ));
+    }
+
+    #[test]
+    fn parse_naive_datetime() {
+        let expected = NaiveDateTime::from(
+            chrono::NaiveDate::from_ymd_opt(2023, 10, 5)
+                .unwrap()
+                .and_hms_opt(14, 30, 0)
+                .unwrap(),
+        );
+        assert_eq!(
+            NaiveDateTime::from_str("2023-10-05T14:30:00").unwrap(),
+            expected
+        );
+        assert_eq!(
+            NaiveDateTime::from_str("2023-10-05 14:30:00").unwrap(),
+            expected
+        );
+        assert!(matches!(
+            NaiveDateTime::from_str("2023-10-05").unwrap_err(),
+            CordError::ParseError { .. }
+        ));
+    }
+
+    #[test]
+    fn bytes_lookup_by_slice() {
+        let mut map: HashMap<Bytes, u8> = HashMap::new();
+        map.insert(Bytes::from(vec![0, 1, 2]), 7);
+        assert_eq!(map.get(&[0_u8, 1, 2][..]), Some(&7));
+
+        let set: BTreeSet<Bytes> = vec![Bytes::from(vec![3]), Bytes::from(vec![1])]
+            .into_iter()
+            .collect();
+        assert!(set.contains(&[1_u8][..]));
+    }
+
+    #[test]
+    fn bytes_compare_with_slices() {
+        let bytes = Bytes::from(vec![0, 1, 2]);
+        assert_eq!(bytes, vec![0, 1, 2]);
+        assert_eq!(bytes, [0_u8, 1, 2][..]);
+        assert_ne!(bytes, [0_u8, 1][..]);
+    }
+
+    #[test]
+    fn bytes_as_slice_and_into_vec() {
+        let bytes = Bytes::from(vec![0, 1, 2]);
+        assert_eq!(bytes.as_slice(), [0, 1, 2]);
+
+        let pointer = bytes.as_slice().as_ptr();
+        let vector = bytes.into_vec();
+        assert_eq!(vector, [0, 1, 2]);
+        assert_eq!(vector.as_ptr(), pointer);
+    }
+
+    #[test]
+    fn bytes_from_slice() {
+        assert_eq!(Bytes::from(&[0_u8, 1, 2][..]), [0, 1, 2][..]);
+        assert_eq!(Bytes::try_new(&[0, 1, 2], 3).unwrap(), [0, 1, 2][..]);
+        assert_eq!(
+            Bytes::try_new(&[0, 1, 2], 2).unwrap_err(),
+            CordError::ValidationError("byte string too long")
+        );
+    }
+
+    #[test]
+    fn set_into_owned_collections() {
+        let set = Set::from(vec![String::from("a"), String::from("b")]);
+
+        let hashset: HashSet<String> = set.clone().into();
+        assert_eq!(hashset.len(), 2);
+        assert!(hashset.contains("a"));
+
+        let mut vector: Vec<String> = set.into();
+        vector.sort();
+        assert_eq!(vector, vec![String::from("a"), String::from("b")]);
+    }
+
+    fn expected_datetime() -> DateTime {
+        chrono::DateTime::parse_from_rfc3339("2023-10-05T14:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+            .into()
+    }
+
+    #[test]
+    fn parse_datetime_common_formats() {
+        for input in [
+            "2023-10-05T14:30:00Z",
+            "2023-10-05T14:30:00.000Z",
+            "2023-10-05 14:30:00Z",
+            "2023-10-05T16:30:00+02:00",
+            "2023-10-05T14:30:00",
+            "2023-10-05 14:30:00",
+            "2023-10-05 14:30:00.000",
+            "Thu, 05 Oct 2023 14:30:00 +0000",
+        ] {
+            assert_eq!(DateTime::from_str(input).unwrap(), expected_datetime());
+        }
+    }
+
+    #[test]
+    fn parse_datetime_invalid() {
+        assert_eq!(
+            DateTime::from_str("yesterday").unwrap_err(),
+            CordError::ParseError {
+                input: String::from("yesterday"),
+                detail: String::from("input contains invalid characters"),
+            }
+        );
+        assert_eq!(
+            DateTime::from_str("2023-13-05T14:30:00Z")
+                .unwrap_err()
+                .to_string(),
+            "Cord could not parse \"2023-13-05T14:30:00Z\": input is out of range"
+        );
+    }
+
+    #[test]
+    fn parse_datetime_with_format() {
+        assert_eq!(
+            DateTime::parse_with_format("05/10/2023 14:30", "%d/%m/%Y %H:%M").unwrap(),
+            expected_datetime()
+        );
+        assert_eq!(
+            DateTime::parse_with_format("05/10/2023 16:30 +0200", "%d/%m/%Y %H:%M %z").unwrap(),
+            expected_datetime()
+        );
+        assert_eq!(
+            DateTime::parse_with_format("32/10/2023 14:30", "%d/%m/%Y %H:%M").unwrap_err(),
+            CordError::ParseError {
+                input: String::from("32/10/2023 14:30"),
+                detail: String::from("input is out of range"),
+            }
+        );
+    }
+
+    #[test]
+    fn datetime_into_chrono() {
+        let datetime = expected_datetime();
+        let borrowed: chrono::DateTime<Utc> = (&datetime).into();
+        let owned: chrono::DateTime<Utc> = datetime.clone().into();
+
+        assert_eq!(borrowed, datetime.chrono);
+        assert_eq!(owned, datetime.chrono);
+    }
+
+    #[test]
+    fn datetime_with_offset_equality_includes_offset() {
+        let utc = chrono::DateTime::parse_from_rfc3339("2023-10-05T14:30:00+00:00").unwrap();
+        let local = chrono::DateTime::parse_from_rfc3339("2023-10-05T16:30:00+02:00").unwrap();
+
+        assert_ne!(
+            DateTimeWithOffset::from(utc),
+            DateTimeWithOffset::from(local)
+        );
+        assert_eq!(
+            DateTime::from(&DateTimeWithOffset::from(utc)),
+            DateTime::from(&DateTimeWithOffset::from(local))
+        );
+    }
+}
@@ -0,0 +1,435 @@
+//! Encodes an enum with variant indices of its own choosing rather than their positions, for
+//! use with `#[serde(with = "cord::variant_index")]`.
+//!
+//! serde's derive numbers variants in declaration order. Where a wire protocol numbers them
+//! differently, the enum implements [`VariantIndices`] to list the index written for each
+//! variant, and the order of declaration no longer matters. Unlike
+//! [`discriminant`](crate::discriminant), the variants may carry data. Formats that identify
+//! variants by name are unaffected.
+
+use serde::de::{self, DeserializeSeed, EnumAccess, IntoDeserializer, Visitor};
+use serde::ser::{self, Impossible};
+use serde::{forward_to_deserialize_any, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::Formatter;
+
+/// The index written for each variant of an enum, in declaration order.
+///
+/// The indices must be distinct. A table that gives two variants the same index is rejected when
+/// encoding or decoding.
+pub trait VariantIndices {
+    const INDICES: &'static [u32];
+}
+
+pub fn serialize<S, E>(value: &E, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    E: Serialize + VariantIndices,
+{
+    value.serialize(RemapSerializer {
+        inner: serializer,
+        indices: distinct(E::INDICES).map_err(ser::Error::custom)?,
+    })
+}
+
+pub fn deserialize<'de, D, E>(deserializer: D) -> Result<E, D::Error>
+where
+    D: Deserializer<'de>,
+    E: Deserialize<'de> + VariantIndices,
+{
+    E::deserialize(RemapDeserializer {
+        inner: deserializer,
+        indices: distinct(E::INDICES).map_err(de::Error::custom)?,
+    })
+}
+
+// Checks that no two variants share an index, which would leave decoding ambiguous
+fn distinct(indices: &'static [u32]) -> Result<&'static [u32], String> {
+    for (position, index) in indices.iter().enumerate() {
+        if indices[..position].contains(index) {
+            return Err(format!("duplicate variant index {index}"));
+        }
+    }
+    Ok(indices)
+}
+
+struct RemapSerializer<S> {
+    inner: S,
+    indices: &'static [u32],
+}
+
+impl<S: Serializer> RemapSerializer<S> {
+    fn remap(&self, variant_index: u32) -> Result<u32, S::Error> {
+        self.indices
+            .get(variant_index as usize)
+            .copied()
+            .ok_or_else(|| {
+                ser::Error::custom(format!("variant {variant_index} has no index assigned"))
+            })
+    }
+}
+
+fn not_an_enum<E: ser::Error>() -> E {
+    ser::Error::custom("variant indices apply only to enums")
+}
+
+macro_rules! reject_non_enums {
+    ($(($type:ty, $name:ident)),*) => {
+        $(
+            fn $name(self, _v: $type) -> Result<S::Ok, S::Error> {
+                Err(not_an_enum())
+            }
+        )*
+    };
+}
+
+impl<S: Serializer> Serializer for RemapSerializer<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = Impossible<S::Ok, S::Error>;
+    type SerializeTuple = Impossible<S::Ok, S::Error>;
+    type SerializeTupleStruct = Impossible<S::Ok, S::Error>;
+    type SerializeTupleVariant = S::SerializeTupleVariant;
+    type SerializeMap = Impossible<S::Ok, S::Error>;
+    type SerializeStruct = Impossible<S::Ok, S::Error>;
+    type SerializeStructVariant = S::SerializeStructVariant;
+
+    reject_non_enums!(
+        (bool, serialize_bool),
+        (i8, serialize_i8),
+        (i16, serialize_i16),
+        (i32, serialize_i32),
+        (i64, serialize_i64),
+        (u8, serialize_u8),
+        (u16, serialize_u16),
+        (u32, serialize_u32),
+        (u64, serialize_u64),
+        (f32, serialize_f32),
+        (f64, serialize_f64),
+        (char, serialize_char),
+        (&str, serialize_str),
+        (&[u8], serialize_bytes)
+    );
+
+    fn serialize_none(self) -> Result<S::Ok, S::Error> {
+        Err(not_an_enum())
+    }
+
+    fn serialize_some<T>(self, _value: &T) -> Result<S::Ok, S::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(not_an_enum())
+    }
+
+    fn serialize_unit(self) -> Result<S::Ok, S::Error> {
+        Err(not_an_enum())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<S::Ok, S::Error> {
+        Err(not_an_enum())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<S::Ok, S::Error> {
+        let variant_index = self.remap(variant_index)?;
+        self.inner
+            .serialize_unit_variant(name, variant_index, variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, _value: &T) -> Result<S::Ok, S::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(not_an_enum())
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let variant_index = self.remap(variant_index)?;
+        self.inner
+            .serialize_newtype_variant(name, variant_index, variant, value)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, S::Error> {
+        Err(not_an_enum())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, S::Error> {
+        Err(not_an_enum())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, S::Error> {
+        Err(not_an_enum())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, S::Error> {
+        let variant_index = self.remap(variant_index)?;
+        self.inner
+            .serialize_tuple_variant(name, variant_index, variant, len)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+        Err(not_an_enum())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, S::Error> {
+        Err(not_an_enum())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, S::Error> {
+        let variant_index = self.remap(variant_index)?;
+        self.inner
+            .serialize_struct_variant(name, variant_index, variant, len)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+struct RemapDeserializer<D> {
+    inner: D,
+    indices: &'static [u32],
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for RemapDeserializer<D> {
+    type Error = D::Error;
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, D::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(de::Error::custom("variant indices apply only to enums"))
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, D::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.inner.deserialize_enum(
+            name,
+            variants,
+            RemapVisitor {
+                inner: visitor,
+                indices: self.indices,
+            },
+        )
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map struct identifier
+        ignored_any
+    }
+}
+
+struct RemapVisitor<V> {
+    inner: V,
+    indices: &'static [u32],
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for RemapVisitor<V> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        self.inner.expecting(formatter)
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: EnumAccess<'de>,
+    {
+        self.inner.visit_enum(RemapEnumAccess {
+            inner: data,
+            indices: self.indices,
+        })
+    }
+}
+
+struct RemapEnumAccess<A> {
+    inner: A,
+    indices: &'static [u32],
+}
+
+impl<'de, A: EnumAccess<'de>> EnumAccess<'de> for RemapEnumAccess<A> {
+    type Error = A::Error;
+    type Variant = A::Variant;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, A::Variant), A::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        self.inner.variant_seed(RemapSeed {
+            inner: seed,
+            indices: self.indices,
+        })
+    }
+}
+
+struct RemapSeed<T> {
+    inner: T,
+    indices: &'static [u32],
+}
+
+impl<'de, T: DeserializeSeed<'de>> DeserializeSeed<'de> for RemapSeed<T> {
+    type Value = T::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<T::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl<'de, T: DeserializeSeed<'de>> Visitor<'de> for RemapSeed<T> {
+    type Value = T::Value;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("variant identifier")
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<T::Value, E>
+    where
+        E: de::Error,
+    {
+        let position = self
+            .indices
+            .iter()
+            .position(|index| u64::from(*index) == v)
+            .ok_or_else(|| de::Error::custom(format!("unknown variant index {v}")))?;
+        self.inner
+            .deserialize((position as u32).into_deserializer())
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<T::Value, E>
+    where
+        E: de::Error,
+    {
+        self.inner.deserialize(v.into_deserializer())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VariantIndices;
+    use crate::{deserialize, serialize, CordError};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Message {
+        Data(Vec<u8>),
+        Ping,
+        Close { code: u16 },
+        Ack(u8, u8),
+    }
+
+    impl VariantIndices for Message {
+        const INDICES: &'static [u32] = &[7, 1, 300, 2];
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Frame {
+        #[serde(with = "crate::variant_index")]
+        message: Message,
+    }
+
+    #[test]
+    fn variant_index_roundtrip() {
+        let cases = [
+            (Message::Data(vec![9]), vec![7, 1, 9]),
+            (Message::Ping, vec![1]),
+            (Message::Close { code: 5 }, vec![172, 2, 5]),
+            (Message::Ack(3, 4), vec![2, 3, 4]),
+        ];
+        for (message, expected) in cases {
+            let frame = Frame { message };
+            let bytes = serialize(&frame).unwrap();
+            assert_eq!(bytes, expected);
+            assert_eq!(deserialize::<Frame>(&bytes).unwrap(), frame);
+        }
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Clash {
+        First,
+        Second,
+    }
+
+    impl VariantIndices for Clash {
+        const INDICES: &'static [u32] = &[4, 4];
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct ClashFrame {
+        #[serde(with = "crate::variant_index")]
+        clash: Clash,
+    }
+
+    #[test]
+    fn variant_index_duplicate() {
+        let err = serialize(&ClashFrame {
+            clash: Clash::First,
+        })
+        .unwrap_err();
+        assert_eq!(
+            err.root_cause(),
+            &CordError::SerializationError(String::from("duplicate variant index 4"))
+        );
+        let err = deserialize::<ClashFrame>(&[4]).unwrap_err();
+        assert_eq!(
+            err.root_cause(),
+            &CordError::DeserializationError(String::from("duplicate variant index 4"))
+        );
+    }
+
+    #[test]
+    fn variant_index_unknown() {
+        let err = deserialize::<Frame>(&[0]).unwrap_err();
+        assert_eq!(
+            err.root_cause(),
+            &CordError::DeserializationError(String::from("unknown variant index 0"))
+        );
+    }
+}
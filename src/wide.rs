@@ -0,0 +1,81 @@
+// Varints for 128-bit integers, which `integer-encoding` does not cover. They follow the same
+// LEB128 and zigzag rules, so values that fit in 64 bits are encoded identically.
+
+const MSB: u8 = 0b1000_0000;
+
+// A `u128` never occupies more than nineteen bytes
+const MAX_LEN: usize = 19;
+
+pub(crate) fn encode_u128(mut value: u128) -> Vec<u8> {
+    let mut output = Vec::with_capacity(MAX_LEN);
+    while value >= u128::from(MSB) {
+        output.push(value as u8 | MSB);
+        value >>= 7;
+    }
+    output.push(value as u8);
+    output
+}
+
+pub(crate) fn decode_u128(input: &[u8]) -> Option<(u128, usize)> {
+    let mut value: u128 = 0;
+    for (index, byte) in input.iter().take(MAX_LEN).enumerate() {
+        let bits = u128::from(byte & !MSB);
+        let shift = 7 * index as u32;
+        if bits.leading_zeros() < shift {
+            return None;
+        }
+        value |= bits << shift;
+        if byte & MSB == 0 {
+            return Some((value, index + 1));
+        }
+    }
+    None
+}
+
+pub(crate) fn encode_i128(value: i128) -> Vec<u8> {
+    encode_u128(((value << 1) ^ (value >> 127)) as u128)
+}
+
+pub(crate) fn decode_i128(input: &[u8]) -> Option<(i128, usize)> {
+    let (value, len) = decode_u128(input)?;
+    Some((((value >> 1) as i128) ^ -((value & 1) as i128), len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_i128, decode_u128, encode_i128, encode_u128};
+    use integer_encoding::VarInt;
+
+    #[test]
+    fn matches_64_bit_varints() {
+        for value in [0_u64, 127, 128, 300, u64::MAX] {
+            assert_eq!(encode_u128(value.into()), value.encode_var_vec());
+        }
+        for value in [i64::MIN, -300, -1, 0, 1, 300, i64::MAX] {
+            assert_eq!(encode_i128(value.into()), value.encode_var_vec());
+        }
+    }
+
+    #[test]
+    fn round_trips_extremes() {
+        for value in [0, 1, u128::from(u64::MAX) + 1, u128::MAX] {
+            let encoded = encode_u128(value);
+            assert_eq!(decode_u128(&encoded), Some((value, encoded.len())));
+        }
+        assert_eq!(encode_u128(u128::MAX).len(), 19);
+        for value in [i128::MIN, -1, 0, i128::MAX] {
+            let encoded = encode_i128(value);
+            assert_eq!(decode_i128(&encoded), Some((value, encoded.len())));
+        }
+    }
+
+    #[test]
+    fn rejects_overflow_and_truncation() {
+        let mut overflowing = vec![0xff; 18];
+        overflowing.push(0x04);
+        assert_eq!(decode_u128(&overflowing), None);
+        assert_eq!(decode_u128(&[0xff; 20]), None);
+        assert_eq!(decode_u128(&[0x80]), None);
+        assert_eq!(decode_u128(&[]), None);
+    }
+}
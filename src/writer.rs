@@ -0,0 +1,148 @@
+use std::cell::Cell;
+use std::hash::Hasher;
+use std::io::Write;
+
+pub(crate) struct SliceWriter<'a> {
+    buffer: &'a mut [u8],
+    position: usize,
+}
+
+impl<'a> SliceWriter<'a> {
+    pub(crate) fn new(buffer: &'a mut [u8]) -> Self {
+        Self {
+            buffer,
+            position: 0,
+        }
+    }
+
+    pub(crate) fn position(&self) -> usize {
+        self.position
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+}
+
+// Writes past the end of the buffer are discarded but still counted, so that the
+// caller can report exactly how many bytes the value needed.
+impl Write for SliceWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let end = self.position.saturating_add(buf.len());
+        if end <= self.buffer.len() {
+            self.buffer[self.position..end].copy_from_slice(buf);
+        }
+        self.position = end;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+pub(crate) struct CountingWriter<'a, W: ?Sized> {
+    inner: &'a mut W,
+    count: usize,
+}
+
+impl<'a, W: ?Sized> CountingWriter<'a, W> {
+    pub(crate) fn new(inner: &'a mut W) -> Self {
+        Self { inner, count: 0 }
+    }
+
+    pub(crate) fn count(&self) -> usize {
+        self.count
+    }
+}
+
+impl<W: ?Sized + Write> Write for CountingWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.count += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+pub(crate) struct HashWriter<'a, H: ?Sized> {
+    hasher: &'a mut H,
+}
+
+impl<'a, H: ?Sized> HashWriter<'a, H> {
+    pub(crate) fn new(hasher: &'a mut H) -> Self {
+        Self { hasher }
+    }
+}
+
+impl<H: ?Sized + Hasher> Write for HashWriter<'_, H> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.hasher.write(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+// Bytes the output may still grow by, shared between the writer of the output and those of
+// the buffers that hold maps and sequences of unknown length until they are written out.
+// Bytes are charged as they are first written, and refunded as a buffer is copied out.
+pub(crate) struct OutputBudget {
+    remaining: Cell<usize>,
+    exceeded: Cell<bool>,
+}
+
+impl OutputBudget {
+    pub(crate) fn new(limit: usize) -> Self {
+        Self {
+            remaining: Cell::new(limit),
+            exceeded: Cell::new(false),
+        }
+    }
+
+    pub(crate) fn exceeded(&self) -> bool {
+        self.exceeded.get()
+    }
+
+    pub(crate) fn refund(&self, len: usize) {
+        self.remaining.set(self.remaining.get().saturating_add(len));
+    }
+}
+
+// Fails any write that would take the total past the budget, recording that it did so that
+// the caller can tell the failure apart from one of the inner writer.
+pub(crate) struct LimitWriter<'a, W: ?Sized> {
+    inner: &'a mut W,
+    budget: &'a OutputBudget,
+}
+
+impl<'a, W: ?Sized> LimitWriter<'a, W> {
+    pub(crate) fn new(inner: &'a mut W, budget: &'a OutputBudget) -> Self {
+        Self { inner, budget }
+    }
+}
+
+impl<W: ?Sized + Write> Write for LimitWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let remaining = self.budget.remaining.get();
+        if buf.len() > remaining {
+            self.budget.exceeded.set(true);
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "output limit exceeded",
+            ));
+        }
+        let written = self.inner.write(buf)?;
+        self.budget.remaining.set(remaining - written);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}